@@ -0,0 +1,39 @@
+use chart_relative::{Chart, canvas::Canvas, params::*};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+fn bench_render(c: &mut Criterion) {
+    // as written to a terminal
+    colored::control::set_override(true);
+
+    let data = (0..40u32).map(|i| (i * 37) % 101).collect::<Vec<_>>();
+    let compare = data.iter().map(|v| v * 9 / 10).collect::<Vec<_>>();
+    let chart = Chart::new(
+        &data,
+        Some(ChartComparison { data: &compare }),
+        ChartOptions {
+            height: 8,
+            view: ViewPreference::Bottom,
+            ..ChartOptions::default()
+        },
+    );
+
+    // a new canvas and text for every chart, against buffers reused from one to the next
+    let mut group = c.benchmark_group("render");
+    group.bench_function("to_string", |b| b.iter(|| black_box(&chart).to_string()));
+    let (mut canvas, mut text) = (Canvas::default(), String::new());
+    group.bench_function("render_into", |b| {
+        b.iter(|| {
+            text.clear();
+            black_box(&chart).render_into(&mut canvas, &mut text);
+        })
+    });
+    let mut out = std::io::BufWriter::new(std::io::sink());
+    group.bench_function("write_to", |b| {
+        b.iter(|| black_box(&chart).write_to(&mut canvas, &mut out))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_render);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+//! Series saved by one run, to be compared with the input of a later one.
+
+use crate::json;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+
+pub const DOCS: &str = r#"
+    --save-baseline NAME stores the values and labels of the first column in
+    .chart-relative/baselines/NAME.json, below the current directory.
+    A later run with --baseline NAME compares its input with them, matching values
+    by label as for two input files. The input should then have a single column.
+
+    chart-relative --save-baseline main < before.txt
+    chart-relative --baseline main < after.txt
+
+    Given both options, the baseline is loaded before it's replaced, so
+    --baseline main --save-baseline main compares each run with the previous one.
+
+    The baseline is a JSON array of records, which --input-format-json can also read.
+"#;
+
+/// Baselines are saved in this directory, relative to the current directory.
+pub const DIR: &str = ".chart-relative/baselines";
+
+/// The file holding the baseline `name`, within `dir`.
+pub fn path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let valid = |c: char| c.is_alphanumeric() || "-_.".contains(c);
+    if name.is_empty() || name.starts_with('.') || !name.chars().all(valid) {
+        return Err(format!(
+            "invalid name '{name}': expected letters, digits, '-', '_' or '.'"
+        ));
+    }
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// Write `values` and `labels` to the baseline `name`, replacing it if it exists.
+pub fn save(dir: &Path, name: &str, values: &[u32], labels: &[String]) -> Result<PathBuf, String> {
+    let path = path(dir, name)?;
+    let records = match labels.is_empty() {
+        true => values.iter().map(|v| json!(v)).collect::<Vec<_>>(),
+        false => values
+            .iter()
+            .zip(labels)
+            .map(|(v, l)| json!({ "label": l, "value": v }))
+            .collect(),
+    };
+    let text = serde_json::to_string_pretty(&Value::Array(records)).unwrap();
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, text + "\n"))
+        .map_err(|e| format!("unable to write '{}': {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Read the values and labels of the baseline `name`.
+pub fn load(dir: &Path, name: &str) -> Result<(Vec<u32>, Vec<String>), String> {
+    let path = path(dir, name)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("unable to read '{}': {e}", path.display()))?;
+    let fields = json::Fields {
+        label: "label",
+        value: "value",
+        compare: "compare",
+    };
+    match json::parse_json(&text, &fields) {
+        Ok((values, _, labels)) => Ok((values, labels)),
+        Err(e) => Err(format!("invalid JSON in '{}': {e}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("chart-relative-{}", std::process::id()));
+        let labels = vec!["a".to_string(), "b c".to_string()];
+        let saved = save(&dir, "main", &[3, 0], &labels).unwrap();
+        assert_eq!(saved, dir.join("main.json"));
+        assert_eq!(load(&dir, "main"), Ok((vec![3, 0], labels)));
+
+        // an unlabeled series replaces the previous one
+        save(&dir, "main", &[1, 2, 3], &[]).unwrap();
+        assert_eq!(load(&dir, "main"), Ok((vec![1, 2, 3], vec![])));
+
+        assert!(load(&dir, "missing").is_err());
+        for name in ["", "../main", ".hidden", "a/b"] {
+            assert!(save(&dir, name, &[1], &[]).is_err());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
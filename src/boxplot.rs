@@ -0,0 +1,401 @@
+//! Box plots of several sets of samples, side by side.
+
+use crate::canvas::{Canvas, Cell, Color, Role, Style};
+use crate::markdown::{escape, write_code_block, write_table};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::Float;
+use crate::params::{LabelTruncation, Series};
+use crate::stats::BoxStats;
+use crate::{GLYPHS, write_label_table};
+
+/// Parameters for creating a `BoxPlot`.
+#[derive(Debug)]
+pub struct BoxPlotOptions<'a> {
+    /// The vertical size of the plot, in lines of text.
+    pub height: u16,
+    /// A label for each set of samples, written in a table below the plot.
+    pub labels: Option<&'a [&'a str]>,
+    /// Written above the plot.
+    pub title: Option<&'a str>,
+    /// The width of a label, in columns of the terminal.
+    pub label_width: usize,
+    /// Determines how labels wider than `label_width` are shortened.
+    pub label_truncation: LabelTruncation,
+}
+
+impl Default for BoxPlotOptions<'_> {
+    fn default() -> Self {
+        Self {
+            height: 8,
+            labels: None,
+            title: None,
+            label_width: 12,
+            label_truncation: LabelTruncation::End,
+        }
+    }
+}
+
+/// Display up to 100 sets of samples as vertical box plots. Each box spans the first
+/// to the third quartile and is crossed by `━` at the median. Whiskers `│` reach the
+/// furthest samples within 1.5 interquartile ranges of the box, and samples beyond them
+/// are indicated by `🢁` or `🢃`. A set without samples is indicated by `∅`.
+pub struct BoxPlot<'a> {
+    boxes: Vec<Option<BoxStats>>,
+    options: BoxPlotOptions<'a>,
+}
+
+/// Positions of the parts of a box, in steps above the bottom of the plot.
+struct BoxSteps {
+    lower_whisker: u16,
+    q1: u16,
+    median: u16,
+    q3: u16,
+    upper_whisker: u16,
+    low_outliers: bool,
+    high_outliers: bool,
+}
+
+impl<'a> BoxPlot<'a> {
+    /// Each of `samples` is displayed as a box, in order. NaN samples are ignored.
+    pub fn new(samples: &[Vec<f64>], options: BoxPlotOptions<'a>) -> Self {
+        assert!(!samples.is_empty(), "samples should contain at least 1 set");
+        assert!(
+            samples.len() <= 100,
+            "samples should contain no more than 100 sets"
+        );
+        if let Some(labels) = options.labels {
+            assert_eq!(
+                labels.len(),
+                samples.len(),
+                "label count should equal the number of sets of samples",
+            );
+        }
+
+        Self {
+            boxes: samples.iter().map(|s| BoxStats::of(s)).collect(),
+            options,
+        }
+    }
+
+    /// The statistics drawn for each set of samples, or `None` if it is empty.
+    pub fn stats(&self) -> &[Option<BoxStats>] {
+        &self.boxes
+    }
+
+    /// The values at the bottom and the top of the plot, spanning every whisker and box.
+    /// Interpolated quartiles can lie beyond the whiskers, which end at samples.
+    fn range(&self) -> (f64, f64) {
+        let stats = self.boxes.iter().flatten();
+        let min = stats
+            .clone()
+            .map(|s| s.lower_whisker.min(s.q1))
+            .reduce(f64::min);
+        let max = stats.map(|s| s.upper_whisker.max(s.q3)).reduce(f64::max);
+        match (min, max) {
+            (Some(min), Some(max)) if max > min => (min, max),
+            // a single value is drawn at the bottom
+            (Some(min), _) => (min, min + 1.0),
+            _ => (0.0, 1.0),
+        }
+    }
+
+    /// Lay out the plot as a grid of styled characters.
+    pub fn canvas(&self) -> Canvas {
+        let mut c = Canvas::default();
+        let height = self.options.height;
+        let (min, max) = self.range();
+
+        // a step is 1/8 of a line, as for the bars of a `Chart`
+        let max_step_count = height * 8;
+        let steps = |v: f64| ((v - min) / (max - min) * max_step_count as f64).round() as u16;
+        let boxes = self
+            .boxes
+            .iter()
+            .map(|stats| {
+                stats.as_ref().map(|s| BoxSteps {
+                    lower_whisker: steps(s.lower_whisker),
+                    q1: steps(s.q1),
+                    median: steps(s.median),
+                    q3: steps(s.q3),
+                    upper_whisker: steps(s.upper_whisker),
+                    low_outliers: s.low_outliers > 0,
+                    high_outliers: s.high_outliers > 0,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(title) = self.options.title {
+            let bold = Style {
+                bold: true,
+                ..Style::PLAIN
+            };
+            c.push_str(title, Role::Title, bold);
+            c.end_row();
+        }
+
+        let (min_label, max_label) = (format_value(min), format_value(max));
+        let tick_label_width = core::cmp::max(min_label.len(), max_label.len());
+        // as for bars, boxes are widened when offsets have 2 digits
+        let box_width_chars: usize = if boxes.len() <= 10 { 1 } else { 2 };
+
+        for layer_num in (0..height).rev() {
+            let tick_label = match layer_num {
+                _ if layer_num == height - 1 => &max_label,
+                0 => &min_label,
+                _ => "",
+            };
+            let tick_label = format!("{tick_label:>tick_label_width$}│");
+            c.push_str(&tick_label, Role::Sidebar, Style::PLAIN);
+
+            for (i, steps) in boxes.iter().enumerate() {
+                let color = match i % 2 {
+                    0 => Color::BrightWhite,
+                    _ => Color::White,
+                };
+                let cell = match steps {
+                    Some(steps) => box_cell(steps, layer_num, height, i, Style::fg(color)),
+                    None if layer_num == 0 => Cell {
+                        glyph: '∅',
+                        role: Role::MissingMarker {
+                            offset: i,
+                            series: Series::Primary,
+                        },
+                        style: Style::fg(color),
+                    },
+                    None => Cell::BLANK,
+                };
+                c.push(cell);
+                for _ in 1..box_width_chars {
+                    // a whisker is a thin line, so it isn't widened
+                    match cell.role {
+                        Role::Whisker { .. } => c.push(Cell::BLANK),
+                        _ => c.push(cell),
+                    }
+                }
+            }
+            c.end_row();
+        }
+
+        // write offsets
+        let tick_spacer = " ".repeat(tick_label_width);
+        c.push_str(&format!("{tick_spacer} "), Role::Blank, Style::PLAIN);
+        for i in 0..boxes.len() {
+            let offset = i.to_string();
+            let padding = " ".repeat(box_width_chars.saturating_sub(offset.len()));
+            c.push_str(&offset, Role::Offset(i), Style::PLAIN);
+            c.push_str(&padding, Role::Blank, Style::PLAIN);
+        }
+        c.end_row();
+
+        if let Some(labels) = self.options.labels {
+            let chart_width = tick_label_width + boxes.len() * box_width_chars;
+            let highlighted = vec![false; labels.len()];
+            let label_fit = (self.options.label_width, self.options.label_truncation);
+            write_label_table(&mut c, labels, &highlighted, chart_width as u16, label_fit);
+        }
+
+        c
+    }
+
+    /// The plot as Markdown: a fenced code block holding the plot, followed by a table
+    /// of the statistics of each box, including the number of samples beyond its whiskers.
+    pub fn markdown(&self) -> String {
+        let mut out = String::new();
+        let lines = self
+            .canvas()
+            .rows()
+            .map(|row| row.iter().map(|c| c.glyph).collect::<String>())
+            .collect::<Vec<_>>();
+        write_code_block(lines.iter().map(String::as_str), &mut out);
+
+        let mut header = vec!["#"];
+        if self.options.labels.is_some() {
+            header.push("label");
+        }
+        header.extend([
+            "lower whisker",
+            "q1",
+            "median",
+            "q3",
+            "upper whisker",
+            "outliers",
+        ]);
+        let rows = self.boxes.iter().enumerate().map(|(i, stats)| {
+            let mut row = vec![i.to_string()];
+            if let Some(labels) = self.options.labels {
+                row.push(escape(labels[i]));
+            }
+            match stats {
+                Some(s) => {
+                    let values = [s.lower_whisker, s.q1, s.median, s.q3, s.upper_whisker];
+                    row.extend(values.into_iter().map(format_value));
+                    row.push(format!(
+                        "{} below, {} above",
+                        s.low_outliers, s.high_outliers
+                    ));
+                }
+                None => row.extend(
+                    ["no samples".to_string()]
+                        .into_iter()
+                        .chain(core::iter::repeat_n(String::new(), 5)),
+                ),
+            }
+            row
+        });
+        out.push('\n');
+        write_table(&mut out, &header, rows);
+        out
+    }
+}
+
+/// The cell of a box within the layer `layer_num`, of `height` layers.
+fn box_cell(steps: &BoxSteps, layer_num: u16, height: u16, offset: usize, style: Style) -> Cell {
+    // each layer holds the steps (8 * layer, 8 * layer + 8], and the bottom one also 0
+    let layer_of = |steps: u16| core::cmp::min(steps.saturating_sub(1) / 8, height - 1);
+    let layer_start = layer_num * 8;
+    let (box_bottom, box_top) = (layer_of(steps.q1), layer_of(steps.q3));
+
+    // outliers are indicated just beyond the whiskers, if not covered by the box
+    let low_marker = layer_of(steps.lower_whisker).saturating_sub(1);
+    let high_marker = core::cmp::min(layer_of(steps.upper_whisker) + 1, height - 1);
+
+    let (glyph, role) = match layer_num {
+        layer if layer == layer_of(steps.median) => ('━', Role::Median { offset }),
+        layer if layer > box_bottom && layer < box_top => ('█', bar(offset)),
+        // the box ends within this layer, filling it from the bottom
+        layer if layer == box_top && layer != box_bottom => {
+            let filled = steps.q3.saturating_sub(layer_start).min(8);
+            (GLYPHS[filled as usize], bar(offset))
+        }
+        // the box begins within this layer, which can only be filled from the top
+        layer if layer == box_bottom && layer != box_top => {
+            let glyph = match (layer_start + 8).saturating_sub(steps.q1) {
+                6.. => '█',
+                3.. => '▀',
+                _ => '▔',
+            };
+            (glyph, bar(offset))
+        }
+        layer if layer == high_marker && steps.high_outliers && layer > box_top => {
+            ('🢁', outlier(offset))
+        }
+        layer if layer == low_marker && steps.low_outliers && layer < box_bottom => {
+            ('🢃', outlier(offset))
+        }
+        layer
+            if layer >= layer_of(steps.lower_whisker) && layer <= layer_of(steps.upper_whisker) =>
+        {
+            ('│', Role::Whisker { offset })
+        }
+        _ => return Cell::BLANK,
+    };
+    Cell { glyph, role, style }
+}
+
+fn bar(offset: usize) -> Role {
+    Role::Bar {
+        offset,
+        series: Series::Primary,
+        layer: None,
+    }
+}
+
+fn outlier(offset: usize) -> Role {
+    Role::OutlierMarker {
+        offset,
+        series: Series::Primary,
+    }
+}
+
+/// Format a value as a tick label, with a decimal only if it has a fraction.
+fn format_value(v: f64) -> String {
+    match v.fract() {
+        0.0 => format!("{v:.0}"),
+        _ => format!("{v:.1}"),
+    }
+}
+
+impl core::fmt::Display for BoxPlot<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.canvas())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_plot() {
+        let samples = vec![
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 40.0],
+            vec![10.0, 12.0, 12.5, 13.0, 14.0, 16.0],
+            vec![],
+            vec![-3.0, 4.0, 5.0, 5.0, 6.0, 7.0],
+        ];
+        let plot = BoxPlot::new(
+            &samples,
+            BoxPlotOptions {
+                height: 6,
+                labels: Some(&["first", "second", "empty", "fourth"]),
+                title: Some("samples"),
+                ..BoxPlotOptions::default()
+            },
+        );
+        println!("\nbox plot\n{plot}");
+        let canvas = plot.canvas();
+
+        // title, 6 layers, offsets and 4 rows of the label table
+        assert_eq!(canvas.height(), 12);
+        let column = |offset: usize| {
+            canvas
+                .rows()
+                .skip(1)
+                .take(6)
+                .map(|row| row[3 + offset].glyph)
+                .collect::<String>()
+        };
+        // the range is 1 to 16 in 48 steps: the box spans steps 6 to 19 with the median
+        // at 13, and the outlier of 40 is indicated above the whisker
+        assert_eq!(column(0), "  🢁▃━▔");
+        assert_eq!(column(2), "     ∅");
+        // the low outlier of -3 is indicated below its whisker, at 4
+        assert!(column(3).ends_with('🢃'));
+        assert!(canvas.rows().any(|row| {
+            row.iter()
+                .any(|c| matches!(c.role, Role::Median { offset: 1 }))
+        }));
+
+        let markdown = plot.markdown();
+        assert!(markdown.contains("| 0 | first | 1 | 3 | 5 | 7 | 8 | 0 below, 1 above |\n"));
+        assert!(markdown.contains("| 2 | empty | no samples |"));
+    }
+
+    #[test]
+    fn test_quartiles_beyond_whiskers() {
+        // the interpolated q3 of the second set, 30.25, is above its whisker at 7
+        let samples = [vec![4.0, 20.0, 9.0], vec![5.0, 7.0, 100.0, 1.0]];
+        let plot = BoxPlot::new(
+            &samples,
+            BoxPlotOptions {
+                height: 6,
+                ..BoxPlotOptions::default()
+            },
+        );
+        println!("\nbox plot quartiles beyond whiskers\n{plot}");
+        assert_eq!(plot.range(), (1.0, 30.25));
+        let canvas = plot.canvas();
+        let column = |offset: usize| {
+            canvas
+                .rows()
+                .take(6)
+                .map(|row| row[5 + offset].glyph)
+                .collect::<String>()
+        };
+        assert_eq!(column(1), "█████━");
+    }
+}
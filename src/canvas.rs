@@ -0,0 +1,418 @@
+//! A chart laid out as a grid of styled characters, which can be inspected
+//! or written to output targets other than the terminal.
+
+use alloc::vec::Vec;
+
+use crate::params::Series;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+/// How a character is drawn. The default is the terminal's own style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    /// Color of the character.
+    pub fg: Option<Color>,
+    /// Color of the space behind the character.
+    pub bg: Option<Color>,
+    /// Whether the character is bold.
+    pub bold: bool,
+    /// Whether `fg` and `bg` are swapped.
+    pub reversed: bool,
+}
+
+impl Style {
+    pub(crate) const PLAIN: Style = Style {
+        fg: None,
+        bg: None,
+        bold: false,
+        reversed: false,
+    };
+
+    pub(crate) const fn fg(color: Color) -> Style {
+        Style {
+            fg: Some(color),
+            ..Style::PLAIN
+        }
+    }
+
+    pub(crate) const fn on(self, color: Color) -> Style {
+        Style {
+            bg: Some(color),
+            ..self
+        }
+    }
+}
+
+/// What a character represents. Offsets refer to the bars as displayed, after sorting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Empty space, such as padding.
+    Blank,
+    /// `options.title`.
+    Title,
+    /// `options.y_caption` or `options.x_caption`.
+    Caption,
+    /// Tick labels, reference line tags and the axis line, to the left of the bars.
+    Sidebar,
+    /// Part of a bar.
+    Bar {
+        /// The offset of the bar.
+        offset: usize,
+        /// The series of the bar.
+        series: Series,
+        /// The layer drawn by the character, within a stacked chart.
+        layer: Option<usize>,
+    },
+    /// `🢁` or `🢃` in place of a bar, indicating a value outside of the displayed range.
+    OutlierMarker {
+        /// The offset of the bar.
+        offset: usize,
+        /// The series of the bar.
+        series: Series,
+    },
+    /// `⨯` in place of a bar, indicating a value of 0.
+    ZeroMarker {
+        /// The offset of the bar.
+        offset: usize,
+        /// The series of the bar.
+        series: Series,
+    },
+    /// `∅` in place of a bar, indicating a missing value.
+    MissingMarker {
+        /// The offset of the bar.
+        offset: usize,
+        /// The series of the bar.
+        series: Series,
+    },
+    /// `│` of a box plot, spanning the samples outside of the box.
+    Whisker {
+        /// The offset of the box.
+        offset: usize,
+    },
+    /// `━` across the box of a box plot, at the median.
+    Median {
+        /// The offset of the box.
+        offset: usize,
+    },
+    /// A reference line, drawn through empty space.
+    ReferenceLine,
+    /// The numeric offset of a bar, below it or next to its label.
+    Offset(usize),
+    /// The label of a bar, below it or in the label table.
+    Label(usize),
+    /// An entry of the legend.
+    Legend,
+    /// The sample which begins an entry of the legend, in the color of the bars it names.
+    Swatch(Swatch),
+    /// The table of statistics.
+    Summary,
+}
+
+/// Bars drawn in the same color, named by an entry of the legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swatch {
+    /// Bars of `data`, without a color of their own.
+    Primary,
+    /// Bars of `compare.data` which are at most the primary value.
+    Improved,
+    /// Bars of `compare.data` which are above the primary value.
+    Regressed,
+    /// Bars of `data` given by `options.highlight`.
+    Highlighted,
+    /// A layer of a stacked chart, by its index in `layers`.
+    Layer(usize),
+}
+
+/// A character of a `Canvas`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// The character to display.
+    pub glyph: char,
+    /// What the character represents.
+    pub role: Role,
+    /// How the character is drawn.
+    pub style: Style,
+}
+
+impl Cell {
+    pub(crate) const BLANK: Cell = Cell {
+        glyph: ' ',
+        role: Role::Blank,
+        style: Style::PLAIN,
+    };
+}
+
+/// Rows of cells, top to bottom. Rows may have different lengths.
+/// The `Display` implementation writes each row as a line of colored text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Canvas {
+    // the cells of every row, one after the other
+    cells: Vec<Cell>,
+    // the offset in `cells` at which each row ends
+    row_ends: Vec<usize>,
+}
+
+impl Canvas {
+    /// Remove every row, keeping room for at least `cells` and `rows`.
+    pub(crate) fn reset(&mut self, cells: usize, rows: usize) {
+        self.cells.clear();
+        self.cells.reserve(cells);
+        self.row_ends.clear();
+        self.row_ends.reserve(rows);
+    }
+
+    /// Every row of cells.
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        (0..self.row_ends.len()).map(|i| self.row_unchecked(i))
+    }
+
+    /// The row at an offset, if any.
+    pub fn row(&self, row: usize) -> Option<&[Cell]> {
+        (row < self.row_ends.len()).then(|| self.row_unchecked(row))
+    }
+
+    /// The cell at a row and column, if any.
+    pub fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.row(row).and_then(|r| r.get(col))
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.row_ends.len()
+    }
+
+    /// The length of the longest row.
+    pub fn width(&self) -> usize {
+        self.rows().map(|r| r.len()).max().unwrap_or(0)
+    }
+
+    fn row_unchecked(&self, row: usize) -> &[Cell] {
+        let start = match row {
+            0 => 0,
+            row => self.row_ends[row - 1],
+        };
+        &self.cells[start..self.row_ends[row]]
+    }
+
+    pub(crate) fn push(&mut self, cell: Cell) {
+        self.cells.push(cell);
+    }
+
+    /// Push each character of `text`, ending the row at each newline.
+    pub(crate) fn push_str(&mut self, text: &str, role: Role, style: Style) {
+        for glyph in text.chars() {
+            match glyph {
+                '\n' => self.end_row(),
+                glyph => self.push(Cell { glyph, role, style }),
+            }
+        }
+    }
+
+    /// Push `cells` as a row of their own.
+    pub(crate) fn push_row(&mut self, cells: &[Cell]) {
+        self.cells.extend_from_slice(cells);
+        self.end_row();
+    }
+
+    pub(crate) fn end_row(&mut self) {
+        self.row_ends.push(self.cells.len());
+    }
+
+    /// Write each row as a line of text, styled with ANSI escape sequences
+    /// unless colors are disabled by `colored::control`, or by omitting the `color` feature.
+    pub(crate) fn write_text(&self, out: &mut dyn core::fmt::Write) -> core::fmt::Result {
+        #[cfg(feature = "color")]
+        let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+        #[cfg(not(feature = "color"))]
+        let colorize = false;
+        for row in self.rows() {
+            // style each run of cells with the same style at once
+            for run in row.chunk_by(|a, b| a.style == b.style) {
+                let style = run[0].style;
+                let styled = colorize && style != Style::PLAIN;
+                if styled {
+                    write_escape(out, &style)?;
+                }
+                for cell in run {
+                    out.write_char(cell.glyph)?;
+                }
+                if styled {
+                    out.write_str("\x1b[0m")?;
+                }
+            }
+            out.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Canvas {
+    /// Write each row as a line of text, as `write_text` does, directly to `out`.
+    pub(crate) fn write_io(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+        // `fmt::Error` carries nothing, so the error of `out` is kept aside
+        struct Adapter<'a, W> {
+            out: &'a mut W,
+            error: Option<std::io::Error>,
+        }
+        impl<W: std::io::Write> core::fmt::Write for Adapter<'_, W> {
+            fn write_str(&mut self, text: &str) -> core::fmt::Result {
+                self.out.write_all(text.as_bytes()).map_err(|error| {
+                    self.error = Some(error);
+                    core::fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter { out, error: None };
+        match self.write_text(&mut adapter) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(adapter.error.expect("only `out` fails")),
+        }
+    }
+}
+
+impl core::fmt::Display for Canvas {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.write_text(f)
+    }
+}
+
+/// Write the sequence which begins `style`, such as `"\x1b[1;97m"`.
+fn write_escape(out: &mut dyn core::fmt::Write, style: &Style) -> core::fmt::Result {
+    let mut codes = [0u8; 4];
+    let mut count = 0;
+    for (enabled, code) in [(style.bold, 1), (style.reversed, 7)] {
+        if enabled {
+            codes[count] = code;
+            count += 1;
+        }
+    }
+    if let Some(bg) = style.bg {
+        codes[count] = ansi_code(bg) + 10;
+        count += 1;
+    }
+    if let Some(fg) = style.fg {
+        codes[count] = ansi_code(fg);
+        count += 1;
+    }
+
+    out.write_str("\x1b[")?;
+    for (i, code) in codes[..count].iter().enumerate() {
+        if i > 0 {
+            out.write_char(';')?;
+        }
+        write!(out, "{code}")?;
+    }
+    out.write_char('m')
+}
+
+/// The code which sets the foreground color. Background codes are 10 more.
+fn ansi_code(color: Color) -> u8 {
+    match color {
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::White => 37,
+        Color::BrightBlack => 90,
+        Color::BrightRed => 91,
+        Color::BrightGreen => 92,
+        Color::BrightYellow => 93,
+        Color::BrightBlue => 94,
+        Color::BrightMagenta => 95,
+        Color::BrightCyan => 96,
+        Color::BrightWhite => 97,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{Chart, params::*};
+
+    #[test]
+    fn test_canvas() {
+        let chart = Chart::new(
+            &[0, 5, 900],
+            Some(ChartComparison { data: &[2, 5, 7] }),
+            ChartOptions {
+                height: 2,
+                view: ViewPreference::Bottom,
+                display: DisplayMode::Portrait {
+                    labels: &["a", "b", "c"],
+                },
+                ..ChartOptions::default()
+            },
+        );
+        let canvas = chart.canvas();
+        // 2 layers, offsets and a row of the label table for each bar
+        assert_eq!(canvas.height(), 6);
+        assert_eq!(canvas.to_string().lines().count(), 6);
+
+        let roles = |row: usize| {
+            canvas
+                .row(row)
+                .unwrap()
+                .iter()
+                .map(|c| c.role)
+                .collect::<Vec<_>>()
+        };
+        let bottom = roles(1);
+        let sidebar_width = bottom.iter().take_while(|&&r| r == Role::Sidebar).count();
+        assert_eq!(canvas.get(1, sidebar_width - 1).unwrap().glyph, '│');
+        let bar = |offset, series| Role::Bar {
+            offset,
+            series,
+            layer: None,
+        };
+        assert_eq!(
+            bottom[sidebar_width..],
+            [
+                Role::ZeroMarker {
+                    offset: 0,
+                    series: Series::Primary
+                },
+                bar(0, Series::Comparison),
+                Role::Blank,
+                bar(1, Series::Primary),
+                bar(1, Series::Comparison),
+                Role::Blank,
+                Role::OutlierMarker {
+                    offset: 2,
+                    series: Series::Primary
+                },
+                bar(2, Series::Comparison),
+                Role::Blank,
+            ]
+        );
+        assert!(roles(2).contains(&Role::Offset(2)));
+        assert!(roles(5).contains(&Role::Label(2)));
+        assert_eq!(
+            canvas.get(1, sidebar_width + 1).unwrap().style.fg,
+            Some(Color::BrightRed)
+        );
+    }
+}
@@ -0,0 +1,511 @@
+//! Default options from a config file, its named profiles and environment variables.
+
+use std::path::{Path, PathBuf};
+
+pub const DOCS: &str = r#"
+    Options which aren't given on the command line are taken from CHART_RELATIVE_*
+    environment variables, or else from the config file at
+    $XDG_CONFIG_HOME/chart-relative/config.toml, or ~/.config/chart-relative/config.toml
+    if XDG_CONFIG_HOME is not set. --print-config shows where each option was found.
+
+    Each option is named as on the command line, without the leading dashes.
+    An option with choices, such as --view-top, is named view and set to top.
+    A flag such as --legend is set to true or false. One which is set to true by the
+    config file or environment is unset by --no-legend, by legend = false in a
+    profile, or by CHART_RELATIVE_LEGEND=false.
+
+    config.toml:
+    max-height = 24
+    view = "top"
+
+    # chosen by --profile words or CHART_RELATIVE_PROFILE=words,
+    # taking priority over the options above
+    [profiles.words]
+    input-format = "ndjson"
+    sort = "primary:desc"
+    legend = true
+
+    environment:
+    CHART_RELATIVE_MAX_HEIGHT=24
+    CHART_RELATIVE_VIEW=top
+"#;
+
+/// Environment variables naming an option start with this, such as `CHART_RELATIVE_MAX_HEIGHT`.
+const ENV_PREFIX: &str = "CHART_RELATIVE_";
+
+/// How an option is given on the command line.
+#[derive(Debug, PartialEq)]
+pub enum Kind {
+    /// `--legend`
+    Flag,
+    /// `--max-height 24`
+    Value,
+    /// `--view-top`, one of the variants
+    Choice(Vec<String>),
+}
+
+/// How an option of this type is given on the command line.
+pub trait OptionKind {
+    fn kind() -> Kind;
+}
+
+/// The variants of an enum which `AutoArgs` reads as a choice, such as `--view-top`.
+pub trait Choices {
+    /// The name of each variant, in `CamelCase`.
+    const VARIANTS: &[&str];
+}
+
+impl OptionKind for bool {
+    fn kind() -> Kind {
+        Kind::Flag
+    }
+}
+
+impl<T: Choices> OptionKind for Option<T> {
+    fn kind() -> Kind {
+        // named in kebab-case by `AutoArgs`, such as `every-nth`
+        let kebab_case = |variant: &&str| {
+            let mut name = String::new();
+            for c in variant.chars() {
+                if c.is_ascii_uppercase() && !name.is_empty() {
+                    name.push('-');
+                }
+                name.push(c.to_ascii_lowercase());
+            }
+            name
+        };
+        Kind::Choice(T::VARIANTS.iter().map(kebab_case).collect())
+    }
+}
+
+macro_rules! value_kind {
+    ($($ty:ty),*) => {
+        $(impl OptionKind for Option<$ty> {
+            fn kind() -> Kind {
+                Kind::Value
+            }
+        })*
+    };
+}
+value_kind!(u16, u32, usize, f64, String);
+
+/// The options of the command line, in order.
+pub struct Options(Vec<(String, Kind)>);
+
+impl Options {
+    /// The options named by fields of the struct which `AutoArgs` reads, such as
+    /// `max_height`, each with the kind of its type.
+    pub fn new(fields: Vec<(&str, Kind)>) -> Self {
+        let options = fields
+            .into_iter()
+            .map(|(field, kind)| (field.replace('_', "-"), kind));
+        Self(options.collect())
+    }
+
+    fn get(&self, key: &str) -> Option<&Kind> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, kind)| kind)
+    }
+
+    /// The arguments which give `value` to the option `key`.
+    fn to_args(&self, key: &str, value: &str) -> Result<Vec<String>, String> {
+        match self.get(key) {
+            Some(Kind::Flag) => match value {
+                "true" => Ok(vec![format!("--{key}")]),
+                "false" => Ok(vec![]),
+                _ => Err(format!(
+                    "expected true or false for '{key}', found '{value}'"
+                )),
+            },
+            Some(Kind::Value) => Ok(vec![format!("--{key}"), value.to_string()]),
+            Some(Kind::Choice(variants)) if variants.iter().any(|v| v == value) => {
+                Ok(vec![format!("--{key}-{value}")])
+            }
+            Some(Kind::Choice(variants)) => Err(format!(
+                "expected one of {} for '{key}', found '{value}'",
+                variants.join(", ")
+            )),
+            None => Err(format!("unknown option '{key}'")),
+        }
+    }
+
+    /// Whether `arg` unsets a flag, such as `--no-legend`, which `AutoArgs` doesn't read.
+    pub fn negates(&self, arg: &str) -> bool {
+        arg.strip_prefix("--no-")
+            .is_some_and(|key| self.get(key) == Some(&Kind::Flag))
+    }
+
+    /// The options given by command line arguments, with their values.
+    /// Other arguments are skipped.
+    fn given_by(&self, args: &[String]) -> Vec<(String, String)> {
+        let mut given = vec![];
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            // a value is either the next argument, or follows `=` as in `--max-height=6`
+            let (flag, inline_value) = match flag.split_once('=') {
+                Some((flag, value)) => (flag, Some(value)),
+                None => (flag, None),
+            };
+            for (key, kind) in &self.0 {
+                match kind {
+                    Kind::Flag if flag == key => given.push((key.clone(), "true".to_string())),
+                    Kind::Flag if flag.strip_prefix("no-") == Some(key) => {
+                        given.push((key.clone(), "false".to_string()))
+                    }
+                    Kind::Value if flag == key => {
+                        let value = inline_value
+                            .map(str::to_string)
+                            .or_else(|| args.next().cloned());
+                        if let Some(value) = value {
+                            given.push((key.clone(), value));
+                        }
+                    }
+                    Kind::Choice(variants) => {
+                        let variant = flag
+                            .strip_prefix(key.as_str())
+                            .and_then(|v| v.strip_prefix('-'))
+                            .filter(|v| variants.iter().any(|variant| variant == v));
+                        if let Some(variant) = variant {
+                            given.push((key.clone(), variant.to_string()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        given
+    }
+}
+
+/// Where the value of an option was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    Profile(String, PathBuf),
+    Env(String),
+    CommandLine,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Profile(name, path) => write!(f, "profile '{name}' of {}", path.display()),
+            Source::Env(name) => write!(f, "environment variable {name}"),
+            Source::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// The value of an option, and where it was found.
+#[derive(Debug, PartialEq)]
+pub struct Setting {
+    pub key: String,
+    pub value: String,
+    pub source: Source,
+}
+
+/// The value of each option which is set, in the order of `options`, and warnings about
+/// environment variables which name no option. From lowest to highest priority, values are
+/// taken from the top level of the config file, then the profile chosen by `--profile` or
+/// `CHART_RELATIVE_PROFILE`, then the environment, then `args`.
+pub fn resolve(
+    options: &Options,
+    args: &[String],
+    env: &[(String, String)],
+    file: Option<(&Path, &str)>,
+) -> Result<(Vec<Setting>, Vec<String>), String> {
+    let mut found: Vec<Setting> = vec![];
+    let mut warnings = vec![];
+    let mut set = |key: &str, value: String, source: Source| -> Result<(), String> {
+        let key = key.to_string();
+        // a profile can't choose another profile
+        let allowed = match key.as_str() {
+            "print-config" => source == Source::CommandLine,
+            "profile" => matches!(source, Source::CommandLine | Source::Env(_)),
+            _ => true,
+        };
+        if !allowed {
+            return Err(format!("'{key}' can't be set by the config file"));
+        }
+        if source != Source::CommandLine {
+            // check that the value is valid for the option
+            options.to_args(&key, &value)?;
+        }
+        found.retain(|s| s.key != key);
+        found.push(Setting { key, value, source });
+        Ok(())
+    };
+
+    let command_line = options.given_by(args);
+    let env = env
+        .iter()
+        .filter_map(|(name, value)| {
+            let key = name
+                .strip_prefix(ENV_PREFIX)?
+                .to_lowercase()
+                .replace('_', "-");
+            // other programs may share the prefix, so these are only reported
+            if options.get(&key).is_none() {
+                warnings.push(format!("{name}: unknown option '{key}', ignored"));
+                return None;
+            }
+            Some((name, key, value))
+        })
+        .collect::<Vec<_>>();
+    let profile = command_line
+        .iter()
+        .find(|(key, _)| key == "profile")
+        .map(|(_, value)| value.as_str())
+        .or_else(|| {
+            env.iter()
+                .find(|(_, key, _)| key == "profile")
+                .map(|(_, _, value)| value.as_str())
+        });
+
+    if let Some((path, text)) = file {
+        let in_file = |e: String| format!("{}: {e}", path.display());
+        let table = text
+            .parse::<toml::Table>()
+            .map_err(|e| in_file(e.message().to_string()))?;
+        for (key, value) in &table {
+            if key != "profiles" {
+                set(
+                    key,
+                    to_string(key, value).map_err(in_file)?,
+                    Source::File(path.into()),
+                )
+                .map_err(in_file)?;
+            }
+        }
+        if let Some(name) = profile {
+            let profile = table
+                .get("profiles")
+                .and_then(|p| p.get(name))
+                .and_then(|p| p.as_table())
+                .ok_or_else(|| in_file(format!("no profile named '{name}'")))?;
+            for (key, value) in profile {
+                let source = Source::Profile(name.to_string(), path.into());
+                set(key, to_string(key, value).map_err(in_file)?, source).map_err(in_file)?;
+            }
+        }
+    } else if let Some(name) = profile {
+        return Err(format!(
+            "no config file, which would define profile '{name}'"
+        ));
+    }
+
+    for (name, key, value) in env {
+        set(&key, value.clone(), Source::Env(name.clone())).map_err(|e| format!("{name}: {e}"))?;
+    }
+    for (key, value) in command_line {
+        set(&key, value, Source::CommandLine)?;
+    }
+
+    found.sort_by_key(|s| options.0.iter().position(|(key, _)| *key == s.key));
+    Ok((found, warnings))
+}
+
+/// The arguments which give the values of `settings` not found on the command line.
+pub fn default_args(options: &Options, settings: &[Setting]) -> Vec<String> {
+    settings
+        .iter()
+        .filter(|s| s.source != Source::CommandLine)
+        // every value was checked by `resolve`
+        .flat_map(|s| options.to_args(&s.key, &s.value).unwrap())
+        .collect()
+}
+
+/// The location of the config file: `$XDG_CONFIG_HOME/chart-relative/config.toml`,
+/// or `$HOME/.config/chart-relative/config.toml`.
+pub fn path() -> Option<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join("chart-relative").join("config.toml"))
+}
+
+fn to_string(key: &str, value: &toml::Value) -> Result<String, String> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        _ => Err(format!("expected a string, integer or boolean for '{key}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct View;
+    impl Choices for View {
+        const VARIANTS: &[&str] = &["Bottom", "Top"];
+    }
+    struct Join;
+    impl Choices for Join {
+        const VARIANTS: &[&str] = &["Label", "EveryLine"];
+    }
+
+    fn options() -> Options {
+        Options::new(vec![
+            ("max_height", Option::<u16>::kind()),
+            ("view", Option::<View>::kind()),
+            ("join", Option::<Join>::kind()),
+            ("legend", bool::kind()),
+            ("sort", Option::<String>::kind()),
+            ("profile", Option::<String>::kind()),
+            ("print_config", bool::kind()),
+        ])
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_options() {
+        let options = options();
+        assert_eq!(options.get("max-height"), Some(&Kind::Value));
+        assert_eq!(options.get("legend"), Some(&Kind::Flag));
+        assert_eq!(
+            options.get("join"),
+            Some(&Kind::Choice(strings(&["label", "every-line"])))
+        );
+        assert_eq!(
+            options.given_by(&strings(&[
+                "a.txt",
+                "--view-top",
+                "--max-height",
+                "4",
+                "--legend"
+            ])),
+            [("view", "top"), ("max-height", "4"), ("legend", "true")]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+        assert!(options.to_args("view", "sideways").is_err());
+
+        // a flag is unset by --no-*, which isn't passed on to `AutoArgs`
+        assert_eq!(
+            options.given_by(&strings(&["--no-legend"])),
+            [("legend".to_string(), "false".to_string())]
+        );
+        assert!(options.negates("--no-legend"));
+        assert!(!options.negates("--no-view"));
+        assert!(!options.negates("--legend"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let options = options();
+        let path = Path::new("config.toml");
+        let file = r#"
+            max-height = 24
+            view = "top"
+            [profiles.words]
+            sort = "label"
+            legend = true
+        "#;
+        let env = [
+            ("CHART_RELATIVE_PROFILE", "words"),
+            ("CHART_RELATIVE_MAX_HEIGHT", "10"),
+            ("HOME", "/home/someone"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let args = strings(&["--view-bottom"]);
+
+        let (settings, warnings) = resolve(&options, &args, &env, Some((path, file))).unwrap();
+        assert!(warnings.is_empty());
+        let found = settings
+            .iter()
+            .map(|s| (s.key.as_str(), s.value.as_str(), s.source.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (
+                    "max-height",
+                    "10",
+                    "environment variable CHART_RELATIVE_MAX_HEIGHT"
+                ),
+                ("view", "bottom", "command line"),
+                ("legend", "true", "profile 'words' of config.toml"),
+                ("sort", "label", "profile 'words' of config.toml"),
+                (
+                    "profile",
+                    "words",
+                    "environment variable CHART_RELATIVE_PROFILE"
+                ),
+            ]
+            .map(|(k, v, s)| (k, v, s.to_string()))
+        );
+        assert_eq!(
+            default_args(&options, &settings),
+            strings(&[
+                "--max-height",
+                "10",
+                "--legend",
+                "--sort",
+                "label",
+                "--profile",
+                "words"
+            ])
+        );
+
+        let unknown = resolve(&options, &[], &[], Some((path, "colour = true")));
+        assert_eq!(unknown.unwrap_err(), "config.toml: unknown option 'colour'");
+        let missing = resolve(
+            &options,
+            &strings(&["--profile", "x"]),
+            &[],
+            Some((path, "")),
+        );
+        assert_eq!(missing.unwrap_err(), "config.toml: no profile named 'x'");
+        let nested = resolve(&options, &[], &[], Some((path, "profile = \"words\"")));
+        assert!(nested.is_err());
+
+        // a value given as `--key=value` replaces the value of the config file
+        let args = strings(&["--max-height=6", "--profile=words"]);
+        assert_eq!(
+            options.given_by(&args),
+            [("max-height", "6"), ("profile", "words")]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+        let (settings, _) = resolve(&options, &args, &[], Some((path, file))).unwrap();
+        assert_eq!(
+            default_args(&options, &settings),
+            strings(&["--view-top", "--legend", "--sort", "label"])
+        );
+
+        // a flag set by the profile is unset by the command line or the environment
+        let env = [("CHART_RELATIVE_LEGEND", "false")].map(|(k, v)| (k.to_string(), v.to_string()));
+        for (args, env) in [(&strings(&["--no-legend"])[..], &[][..]), (&[], &env)] {
+            let profile = strings(&["--profile", "words"]);
+            let args = [args, &profile].concat();
+            let (settings, _) = resolve(&options, &args, env, Some((path, file))).unwrap();
+            assert!(
+                settings
+                    .iter()
+                    .any(|s| s.key == "legend" && s.value == "false")
+            );
+            assert!(!default_args(&options, &settings).contains(&"--legend".to_string()));
+        }
+        // and by a profile, when set by the top level of the config file
+        let file = "legend = true\n[profiles.plain]\nlegend = false";
+        let args = strings(&["--profile", "plain"]);
+        let (settings, _) = resolve(&options, &args, &[], Some((path, file))).unwrap();
+        assert!(!default_args(&options, &settings).contains(&"--legend".to_string()));
+
+        // environment variables which name no option are only reported
+        let env = [("CHART_RELATIVE_COLOUR", "1")].map(|(k, v)| (k.to_string(), v.to_string()));
+        let (settings, warnings) = resolve(&options, &[], &env, None).unwrap();
+        assert!(settings.is_empty());
+        assert_eq!(
+            warnings,
+            ["CHART_RELATIVE_COLOUR: unknown option 'colour', ignored"]
+        );
+    }
+}
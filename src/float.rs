@@ -0,0 +1,153 @@
+//! Float functions of `std` which `core` lacks, calculated by `libm` if it's enabled.
+
+/// The methods of `f64` which are only inherent with `std`.
+pub(crate) trait Float {
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn fract(self) -> Self;
+    fn sqrt(self) -> Self;
+}
+
+#[cfg(feature = "libm")]
+impl Float for f64 {
+    fn floor(self) -> f64 {
+        libm::floor(self)
+    }
+
+    fn ceil(self) -> f64 {
+        libm::ceil(self)
+    }
+
+    fn round(self) -> f64 {
+        libm::round(self)
+    }
+
+    fn fract(self) -> f64 {
+        self - libm::trunc(self)
+    }
+
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+}
+
+#[cfg(not(feature = "libm"))]
+impl Float for f64 {
+    fn floor(self) -> f64 {
+        portable::floor(self)
+    }
+
+    fn ceil(self) -> f64 {
+        portable::ceil(self)
+    }
+
+    fn round(self) -> f64 {
+        portable::round(self)
+    }
+
+    fn fract(self) -> f64 {
+        self - portable::trunc(self)
+    }
+
+    fn sqrt(self) -> f64 {
+        portable::sqrt(self)
+    }
+}
+
+/// Without `libm`, rounding is exact, by conversion to an integer, and `sqrt` is found
+/// by Newton's method, within a unit in the last place.
+#[cfg(any(not(feature = "libm"), test))]
+mod portable {
+    /// Every `f64` of at least this magnitude is an integer.
+    const INTEGRAL: f64 = 4503599627370496.0;
+
+    pub(super) fn trunc(x: f64) -> f64 {
+        match x.abs() < INTEGRAL {
+            true => x as i64 as f64,
+            // also infinite and NaN
+            false => x,
+        }
+    }
+
+    pub(super) fn floor(x: f64) -> f64 {
+        let t = trunc(x);
+        if t > x { t - 1.0 } else { t }
+    }
+
+    pub(super) fn ceil(x: f64) -> f64 {
+        let t = trunc(x);
+        if t < x { t + 1.0 } else { t }
+    }
+
+    /// Round half away from zero, as `f64::round`.
+    pub(super) fn round(x: f64) -> f64 {
+        let t = trunc(x);
+        match (x - t).abs() >= 0.5 {
+            true => t + x.signum(),
+            false => t,
+        }
+    }
+
+    pub(super) fn sqrt(x: f64) -> f64 {
+        if x.is_nan() || x < 0.0 {
+            return f64::NAN;
+        }
+        if x == 0.0 || x.is_infinite() {
+            return x;
+        }
+        // halving the exponent gives an estimate within a factor of 2, from which each
+        // step doubles the number of correct bits
+        let mut root = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+        for _ in 0..8 {
+            let next = (root + x / root) / 2.0;
+            if next == root {
+                break;
+            }
+            root = next;
+        }
+        root
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::portable::*;
+
+    #[test]
+    fn test_without_libm() {
+        let values = [
+            0.0,
+            -0.0,
+            0.4,
+            0.5,
+            0.5000000000000001,
+            0.49999999999999994,
+            -0.5,
+            -1.5,
+            2.5,
+            -7.25,
+            1e15 + 0.5,
+            1e300,
+            -1e300,
+        ];
+        for x in values {
+            assert_eq!(trunc(x), x.trunc(), "trunc {x}");
+            assert_eq!(floor(x), x.floor(), "floor {x}");
+            assert_eq!(ceil(x), x.ceil(), "ceil {x}");
+            assert_eq!(round(x), x.round(), "round {x}");
+        }
+        assert!(floor(f64::NAN).is_nan());
+        assert_eq!(ceil(f64::INFINITY), f64::INFINITY);
+
+        for x in [1.0, 2.0, 0.25, 1e-300, 1e300, 286.7 * 286.7, 12345.678] {
+            let (root, expected) = (sqrt(x), x.sqrt());
+            assert!(
+                (root - expected).abs() <= expected * f64::EPSILON,
+                "sqrt {x}"
+            );
+        }
+        assert!(sqrt(-1.0).is_nan());
+        assert_eq!(sqrt(0.0), 0.0);
+    }
+}
@@ -0,0 +1,256 @@
+//! Several charts laid out side by side.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter;
+use unicode_width::UnicodeWidthChar;
+
+use crate::canvas::{Canvas, Cell, Role, Style};
+use crate::{Chart, Scale};
+
+/// Parameters for creating a `ChartGrid`.
+#[derive(Debug)]
+pub struct GridOptions {
+    /// Scale every chart to the values of all charts, so that their bars can be compared.
+    pub share_scale: bool,
+    /// The horizontal size of the grid, in characters. Charts which don't fit
+    /// are moved to the next row.
+    pub width: usize,
+    /// The number of spaces between charts.
+    pub gap: usize,
+}
+
+impl Default for GridOptions {
+    /// The width of the terminal, or of `COLUMNS` if the output isn't a terminal, or 80
+    /// characters. Without the `std` feature, the width is always 80 characters.
+    fn default() -> Self {
+        #[cfg(feature = "std")]
+        let width = match terminal_size::terminal_size() {
+            Some((terminal_size::Width(width), _)) => width as usize,
+            None => std::env::var("COLUMNS")
+                .ok()
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(80),
+        };
+        #[cfg(not(feature = "std"))]
+        let width = 80;
+
+        Self {
+            share_scale: false,
+            width,
+            gap: 2,
+        }
+    }
+}
+
+/// Display several titled `Chart`s in rows.
+pub struct ChartGrid<'a> {
+    panels: Vec<(&'a str, Chart<'a>)>,
+    options: GridOptions,
+}
+
+impl<'a> ChartGrid<'a> {
+    /// Each chart is displayed below its title, which may be empty.
+    pub fn new(panels: Vec<(&'a str, Chart<'a>)>, options: GridOptions) -> Self {
+        Self { panels, options }
+    }
+
+    /// Lay out the grid as a grid of styled characters, composed of the canvas of each
+    /// chart.
+    pub fn canvas(&self) -> Canvas {
+        let panels = self
+            .panels()
+            .into_iter()
+            .map(|panel| {
+                let width = panel.rows().map(columns).max().unwrap_or(0);
+                (panel, width)
+            })
+            .collect::<Vec<_>>();
+
+        // fill each row with as many charts as fit within the width
+        let mut rows: Vec<&[(Canvas, usize)]> = vec![];
+        let mut row_start = 0;
+        let mut row_width = 0;
+        for (i, (_, width)) in panels.iter().enumerate() {
+            let gap = if i > row_start { self.options.gap } else { 0 };
+            if i > row_start && row_width + gap + width > self.options.width {
+                rows.push(&panels[row_start..i]);
+                row_start = i;
+                row_width = *width;
+            } else {
+                row_width += gap + width;
+            }
+        }
+        rows.push(&panels[row_start..]);
+
+        let mut c = Canvas::default();
+        let mut line = vec![];
+        for (row_num, row) in rows.iter().enumerate() {
+            if row_num > 0 {
+                c.end_row();
+            }
+            let row_height = row
+                .iter()
+                .map(|(panel, _)| panel.height())
+                .max()
+                .unwrap_or(0);
+            for line_num in 0..row_height {
+                line.clear();
+                for (i, (panel, width)) in row.iter().enumerate() {
+                    if i > 0 {
+                        line.extend(iter::repeat_n(Cell::BLANK, self.options.gap));
+                    }
+                    let cells = panel.row(line_num).unwrap_or(&[]);
+                    line.extend_from_slice(cells);
+                    line.extend(iter::repeat_n(Cell::BLANK, width - columns(cells)));
+                }
+                let end = line
+                    .iter()
+                    .rposition(|cell| cell.glyph != ' ' || cell.style != Style::PLAIN)
+                    .map_or(0, |i| i + 1);
+                c.push_row(&line[..end]);
+            }
+        }
+        c
+    }
+
+    /// Lay out each chart below its title.
+    fn panels(&self) -> Vec<Canvas> {
+        let all_measurements = self
+            .panels
+            .iter()
+            .flat_map(|(_, chart)| {
+                let (data, cmp_data) = chart.measurements();
+                data.iter().chain(cmp_data.unwrap_or(&[]).iter()).copied()
+            })
+            .collect::<Vec<_>>();
+
+        let mut chart_canvas = Canvas::default();
+        let mut panels = vec![];
+        for (title, chart) in &self.panels {
+            let scale = match self.options.share_scale {
+                true => Scale::fit(
+                    all_measurements.iter(),
+                    chart.options.height,
+                    &chart.options.view,
+                    chart.y_range(),
+                    chart.options.rounding,
+                ),
+                false => chart.scale(),
+            };
+            chart.render(&scale, &mut chart_canvas);
+
+            let mut panel = Canvas::default();
+            if !title.is_empty() {
+                let bold = Style {
+                    bold: true,
+                    ..Style::PLAIN
+                };
+                panel.push_str(title, Role::Title, bold);
+                panel.end_row();
+            }
+            for row in chart_canvas.rows() {
+                panel.push_row(row);
+            }
+            panels.push(panel);
+        }
+        panels
+    }
+}
+
+/// Count the columns of the terminal which `cells` occupy.
+fn columns(cells: &[Cell]) -> usize {
+    cells
+        .iter()
+        .map(|cell| cell.glyph.width().unwrap_or(0))
+        .sum()
+}
+
+impl<'a> core::fmt::Display for ChartGrid<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.canvas())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::params::*;
+
+    #[test]
+    fn test_columns() {
+        let cells = "█▂ 🢁表"
+            .chars()
+            .map(|glyph| Cell {
+                glyph,
+                ..Cell::BLANK
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(columns(&cells), 6);
+    }
+
+    #[test]
+    fn test_grid() {
+        let days: [&[u32]; 5] = [
+            &[3, 8, 12, 30, 22, 9],
+            &[5, 11, 9, 41, 18, 4],
+            &[0, 2, 7, 12, 9, 2],
+            &[8, 16, 20, 53, 30, 14],
+            &[1, 4, 3, 9, 6, 1],
+        ];
+        let titles = ["mon", "tue", "wed", "thu", "fri"];
+        for share_scale in [false, true] {
+            let panels = titles
+                .iter()
+                .zip(days)
+                .map(|(&title, data)| {
+                    let options = ChartOptions {
+                        height: 4,
+                        view: ViewPreference::Bottom,
+                        ..ChartOptions::default()
+                    };
+                    (title, Chart::new(data, None, options))
+                })
+                .collect();
+            let grid = ChartGrid::new(
+                panels,
+                GridOptions {
+                    share_scale,
+                    width: 40,
+                    gap: 2,
+                },
+            );
+            let output = grid.to_string();
+            println!("\ngrid share_scale={share_scale}\n{output}");
+            assert!(grid.canvas().rows().all(|row| columns(row) <= 40));
+
+            // the top and bottom tick labels, below the title, and the bars of a panel
+            let panels = grid.panels();
+            let ticks = |panel: &Canvas| {
+                [1, 4].map(|row| {
+                    let row = panel.row(row).unwrap().iter();
+                    row.filter(|cell| cell.role == Role::Sidebar)
+                        .map(|cell| cell.glyph)
+                        .collect::<String>()
+                })
+            };
+            let bar = |panel: usize, offset: usize| {
+                let cells = panels[panel].rows().flatten();
+                cells
+                    .filter(|cell| matches!(cell.role, Role::Bar { offset: o, .. } if o == offset))
+                    .map(|cell| cell.glyph)
+                    .collect::<String>()
+            };
+            if share_scale {
+                assert!(panels.iter().all(|panel| ticks(panel) == ticks(&panels[3])));
+                // 9 on monday, tuesday, wednesday and friday, and 12 on monday and wednesday
+                let nines = [bar(0, 5), bar(1, 2), bar(2, 4), bar(4, 3)];
+                assert!(nines.iter().all(|b| *b == nines[0]), "{nines:?}");
+                assert_eq!(bar(0, 2), bar(2, 3));
+                assert_ne!(bar(0, 2), nines[0]);
+            } else {
+                assert_ne!(ticks(&panels[0]), ticks(&panels[3]));
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Drawing a `Canvas` as a PNG image, in the colors of a terminal and a bundled bitmap font.
+
+use crate::canvas::{Canvas, Cell, Color, Style};
+use crate::font;
+use alloc::vec::Vec;
+use unicode_width::UnicodeWidthChar;
+
+/// Parameters for `Canvas::write_png`.
+#[derive(Debug)]
+pub struct PngOptions {
+    /// The size of each pixel of the font, in pixels of the image.
+    pub scale: u32,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self { scale: 2 }
+    }
+}
+
+/// The size of a column of text, before scaling.
+const CELL_WIDTH: usize = 8;
+const CELL_HEIGHT: usize = 16;
+/// Glyphs of the font are centered vertically within their cell.
+const GLYPH_TOP: usize = 4;
+/// Empty space around the text.
+const MARGIN: usize = 8;
+
+type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [0x1e, 0x1e, 0x1e];
+/// The color of text without a color of its own.
+const FOREGROUND: Rgb = [0xcc, 0xcc, 0xcc];
+
+fn rgb(color: Color) -> Rgb {
+    match color {
+        Color::Black => [0x00, 0x00, 0x00],
+        Color::Red => [0xcd, 0x31, 0x31],
+        Color::Green => [0x0d, 0xbc, 0x79],
+        Color::Yellow => [0xe5, 0xe5, 0x10],
+        Color::Blue => [0x24, 0x72, 0xc8],
+        Color::Magenta => [0xbc, 0x3f, 0xbc],
+        Color::Cyan => [0x11, 0xa8, 0xcd],
+        Color::White => [0xe5, 0xe5, 0xe5],
+        Color::BrightBlack => [0x66, 0x66, 0x66],
+        Color::BrightRed => [0xf1, 0x4c, 0x4c],
+        Color::BrightGreen => [0x23, 0xd1, 0x8b],
+        Color::BrightYellow => [0xf5, 0xf5, 0x43],
+        Color::BrightBlue => [0x3b, 0x8e, 0xea],
+        Color::BrightMagenta => [0xd6, 0x70, 0xd6],
+        Color::BrightCyan => [0x29, 0xb8, 0xdb],
+        Color::BrightWhite => [0xff, 0xff, 0xff],
+    }
+}
+
+/// An RGB image, one row after the other.
+struct Pixels {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Pixels {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: BACKGROUND.repeat(width * height),
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for row in y..y + height {
+            let start = (row * self.width + x) * 3;
+            for pixel in self.data[start..start + width * 3].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Each pixel repeated `scale` times in both directions.
+    fn scaled(&self, scale: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.data.len() * scale * scale);
+        for row in self.data.chunks_exact(self.width * 3) {
+            let start = data.len();
+            for pixel in row.chunks_exact(3) {
+                for _ in 0..scale {
+                    data.extend_from_slice(pixel);
+                }
+            }
+            for _ in 1..scale {
+                data.extend_from_within(start..start + self.width * scale * 3);
+            }
+        }
+        data
+    }
+}
+
+impl Canvas {
+    /// Write the canvas as a PNG image. The text is drawn with a font of 8x8 pixels
+    /// which covers ASCII and the markers of charts, in cells of 8x16 pixels, on a dark
+    /// background. Bars and lines are drawn to fill their cells, so that they join up.
+    /// Other characters, such as CJK, are drawn as empty boxes of their width.
+    pub fn write_png(&self, out: impl std::io::Write, options: &PngOptions) -> std::io::Result<()> {
+        let pixels = self.rasterize();
+        let scale = options.scale.max(1);
+        let mut encoder = png::Encoder::new(
+            out,
+            pixels.width as u32 * scale,
+            pixels.height as u32 * scale,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io_error)?;
+        writer
+            .write_image_data(&pixels.scaled(scale as usize))
+            .map_err(io_error)?;
+        writer.finish().map_err(io_error)
+    }
+
+    fn rasterize(&self) -> Pixels {
+        let columns =
+            |row: &[Cell]| -> usize { row.iter().map(|c| c.glyph.width().unwrap_or(0)).sum() };
+        let width = self.rows().map(columns).max().unwrap_or(0);
+        let mut pixels = Pixels::new(
+            width * CELL_WIDTH + 2 * MARGIN,
+            self.height() * CELL_HEIGHT + 2 * MARGIN,
+        );
+        for (row_num, row) in self.rows().enumerate() {
+            let y = MARGIN + row_num * CELL_HEIGHT;
+            let mut x = MARGIN;
+            for cell in row {
+                let width = cell.glyph.width().unwrap_or(0) * CELL_WIDTH;
+                draw_glyph(&mut pixels, x, y, width, cell.glyph, &cell.style);
+                x += width;
+            }
+        }
+        pixels
+    }
+}
+
+/// Draw `glyph` in the cell at `x`, `y`, which is `width` pixels wide.
+fn draw_glyph(pixels: &mut Pixels, x: usize, y: usize, width: usize, glyph: char, style: &Style) {
+    if width == 0 {
+        return;
+    }
+    let (mut fg, mut bg) = (style.fg.map(rgb).unwrap_or(FOREGROUND), style.bg.map(rgb));
+    if style.reversed {
+        (fg, bg) = (bg.unwrap_or(BACKGROUND), Some(fg));
+    }
+    if let Some(bg) = bg {
+        pixels.fill(x, y, width, CELL_HEIGHT, bg);
+    }
+
+    // block elements fill a part of the cell, in eighths
+    let eighth = CELL_HEIGHT / 8;
+    let fill =
+        |pixels: &mut Pixels, top: usize, height: usize| pixels.fill(x, y + top, width, height, fg);
+    match glyph {
+        ' ' => {}
+        '▁'..='█' => {
+            let height = (glyph as usize - '▀' as usize) * eighth;
+            fill(pixels, CELL_HEIGHT - height, height);
+        }
+        '▀' => fill(pixels, 0, 4 * eighth),
+        '▔' => fill(pixels, 0, eighth),
+        '─' => fill(pixels, CELL_HEIGHT / 2 - 1, 2),
+        '━' => fill(pixels, CELL_HEIGHT / 2 - 2, 4),
+        '│' => pixels.fill(x + CELL_WIDTH / 2 - 1, y, 2, CELL_HEIGHT, fg),
+        // short and long dashes, continuing across cells
+        '┄' | '╌' => {
+            let (dash, period) = if glyph == '┄' { (2, 4) } else { (5, 8) };
+            for i in (0..width).filter(|i| i % period < dash) {
+                pixels.fill(x + i, y + CELL_HEIGHT / 2 - 1, 1, 2, fg);
+            }
+        }
+        _ => match font::glyph(glyph) {
+            Some(rows) => {
+                for (row_num, bits) in rows.iter().enumerate() {
+                    // bold glyphs are widened by a pixel to the right
+                    let bits = match style.bold {
+                        true => *bits as u16 | (*bits as u16) << 1,
+                        false => *bits as u16,
+                    };
+                    for col in (0..CELL_WIDTH).filter(|col| bits >> col & 1 == 1) {
+                        pixels.fill(x + col, y + GLYPH_TOP + row_num, 1, 1, fg);
+                    }
+                }
+            }
+            // the outline of a box, in place of a glyph which the font lacks
+            None => {
+                let (top, bottom) = (y + GLYPH_TOP - 1, y + GLYPH_TOP + 8);
+                pixels.fill(x + 1, top, width - 2, 1, fg);
+                pixels.fill(x + 1, bottom, width - 2, 1, fg);
+                pixels.fill(x + 1, top, 1, bottom - top + 1, fg);
+                pixels.fill(x + width - 2, top, 1, bottom - top + 1, fg);
+            }
+        },
+    }
+}
+
+fn io_error(e: png::EncodingError) -> std::io::Error {
+    match e {
+        png::EncodingError::IoError(e) => e,
+        e => std::io::Error::other(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::Role;
+
+    #[test]
+    fn test_write_png() {
+        let mut canvas = Canvas::default();
+        let red = Style::fg(Color::Red);
+        canvas.push_str("A", Role::Title, Style::PLAIN);
+        canvas.push_str("█", Role::Blank, red);
+        canvas.end_row();
+        canvas.push_str("▄東", Role::Blank, Style::PLAIN);
+        canvas.end_row();
+
+        let pixels = canvas.rasterize();
+        // the second row is 3 columns wide
+        assert_eq!(pixels.width, 3 * CELL_WIDTH + 2 * MARGIN);
+        assert_eq!(pixels.height, 2 * CELL_HEIGHT + 2 * MARGIN);
+        let cell = |col: usize, row: usize, x: usize, y: usize| -> Rgb {
+            let (x, y) = (
+                MARGIN + col * CELL_WIDTH + x,
+                MARGIN + row * CELL_HEIGHT + y,
+            );
+            let i = (y * pixels.width + x) * 3;
+            pixels.data[i..i + 3].try_into().unwrap()
+        };
+        // the top of 'A' is 2 pixels in the middle
+        assert_eq!(cell(0, 0, 2, GLYPH_TOP), FOREGROUND);
+        assert_eq!(cell(0, 0, 1, GLYPH_TOP), BACKGROUND);
+        assert_eq!(cell(0, 0, 2, 0), BACKGROUND);
+        assert_eq!(cell(1, 0, 0, 0), rgb(Color::Red));
+        assert_eq!(cell(1, 0, 7, 15), rgb(Color::Red));
+        assert_eq!(cell(0, 1, 0, 7), BACKGROUND);
+        assert_eq!(cell(0, 1, 0, 8), FOREGROUND);
+        // a box 2 columns wide in place of a CJK character
+        assert_eq!(cell(1, 1, 1, GLYPH_TOP - 1), FOREGROUND);
+        assert_eq!(cell(2, 1, 6, GLYPH_TOP + 8), FOREGROUND);
+        assert_eq!(cell(2, 1, 0, GLYPH_TOP + 4), BACKGROUND);
+
+        let mut png = vec![];
+        canvas
+            .write_png(&mut png, &PngOptions { scale: 3 })
+            .unwrap();
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!((info.width, info.height), (3 * 40, 3 * 48));
+        // the red block, scaled
+        let i = ((3 * MARGIN + 1) * info.width as usize + 3 * (MARGIN + CELL_WIDTH)) * 3;
+        assert_eq!(data[i..i + 3], rgb(Color::Red));
+    }
+}
@@ -0,0 +1,181 @@
+//! Reading series from JSON and NDJSON.
+
+use serde_json::Value;
+
+/// Names of the fields holding each part of a record.
+/// Fields of nested objects are selected with `.`, such as `stats.count`.
+pub struct Fields<'a> {
+    pub label: &'a str,
+    pub value: &'a str,
+    pub compare: &'a str,
+}
+
+/// Primary data, comparison data and labels. Either of the latter may be empty.
+pub type Series = (Vec<u32>, Vec<u32>, Vec<String>);
+
+/// Parse an array of numbers, an object mapping labels to values, or an array of records.
+pub fn parse_json(text: &str, fields: &Fields) -> Result<Series, String> {
+    let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let series = match json {
+        // without any records, an array of numbers
+        Value::Array(items) if !items.iter().any(Value::is_object) => {
+            let values = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| match item {
+                    Value::Number(_) => integer(item).map_err(|e| format!("element {i}: {e}")),
+                    _ => Err(format!("element {i} is not a number")),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok((values, vec![], vec![]))
+        }
+        Value::Array(records) => from_records(&records, fields),
+        Value::Object(map) => {
+            let mut series: Series = (vec![], vec![], vec![]);
+            for (label, value) in &map {
+                series
+                    .0
+                    .push(integer(value).map_err(|e| format!("'{label}': {e}"))?);
+                series.2.push(label.clone());
+            }
+            Ok(series)
+        }
+        _ => Err("expected an array or an object".to_string()),
+    };
+    series.and_then(non_empty)
+}
+
+/// Parse one record per line, skipping blank lines.
+pub fn parse_ndjson(text: &str, fields: &Fields) -> Result<Series, String> {
+    let records = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect::<Result<Vec<Value>, _>>()?;
+    from_records(&records, fields).and_then(non_empty)
+}
+
+fn non_empty(series: Series) -> Result<Series, String> {
+    match series.0.is_empty() {
+        true => Err("expected at least 1 value".to_string()),
+        false => Ok(series),
+    }
+}
+
+fn from_records(records: &[Value], fields: &Fields) -> Result<Series, String> {
+    let mut series: Series = (vec![], vec![], vec![]);
+    for (i, record) in records.iter().enumerate() {
+        let context = |e: String| format!("record {}: {e}", i + 1);
+        let value = select(record, fields.value)
+            .ok_or_else(|| context(format!("missing field '{}'", fields.value)))?;
+        series.0.push(integer(value).map_err(context)?);
+        if let Some(compare) = select(record, fields.compare) {
+            series.1.push(integer(compare).map_err(context)?);
+        }
+        if let Some(label) = select(record, fields.label) {
+            series.2.push(match label {
+                Value::String(label) => label.clone(),
+                other => other.to_string(),
+            });
+        }
+    }
+
+    // comparison values and labels are optional, but not for only some records
+    for (len, field) in [
+        (series.1.len(), fields.compare),
+        (series.2.len(), fields.label),
+    ] {
+        if len > 0 && len < records.len() {
+            return Err(format!("field '{field}' is missing from some records"));
+        }
+    }
+    Ok(series)
+}
+
+fn select<'v>(record: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.')
+        .try_fold(record, |value, key| value.get(key))
+}
+
+fn integer(value: &Value) -> Result<u32, String> {
+    value
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| format!("expected an integer from 0 to {}, found {value}", u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: Fields = Fields {
+        label: "label",
+        value: "value",
+        compare: "compare",
+    };
+
+    #[test]
+    fn test_parse_json() {
+        assert_eq!(
+            parse_json("[3, 0, 7]", &FIELDS),
+            Ok((vec![3, 0, 7], vec![], vec![]))
+        );
+        assert_eq!(
+            parse_json(r#"{"b": 2, "a": 1}"#, &FIELDS),
+            Ok((vec![2, 1], vec![], vec!["b".to_string(), "a".to_string()]))
+        );
+        assert_eq!(
+            parse_json(
+                r#"[{"label": "a", "value": 1, "compare": 4}, {"label": 2, "value": 3, "compare": 0}]"#,
+                &FIELDS
+            ),
+            Ok((
+                vec![1, 3],
+                vec![4, 0],
+                vec!["a".to_string(), "2".to_string()]
+            ))
+        );
+        assert!(parse_json("[1.5]", &FIELDS).is_err());
+        assert_eq!(
+            parse_json(r#"[1, "x"]"#, &FIELDS),
+            Err("element 1 is not a number".to_string())
+        );
+        assert_eq!(
+            parse_json("[1, -2]", &FIELDS),
+            Err("element 1: expected an integer from 0 to 4294967295, found -2".to_string())
+        );
+        assert_eq!(
+            parse_json("[]", &FIELDS),
+            Err("expected at least 1 value".to_string())
+        );
+        assert!(parse_json("{}", &FIELDS).is_err());
+        assert!(parse_json(r#"[{"value": 1, "compare": 2}, {"value": 3}]"#, &FIELDS).is_err());
+    }
+
+    #[test]
+    fn test_parse_ndjson() {
+        let fields = Fields {
+            label: "name",
+            value: "stats.before",
+            compare: "stats.after",
+        };
+        let text = r#"{"name": "x", "stats": {"before": 10, "after": 8}}
+
+{"name": "y", "stats": {"before": 5, "after": 9}}
+"#;
+        assert_eq!(
+            parse_ndjson(text, &fields),
+            Ok((
+                vec![10, 5],
+                vec![8, 9],
+                vec!["x".to_string(), "y".to_string()]
+            ))
+        );
+        assert_eq!(
+            parse_ndjson("{\"value\": 1}\n{}", &FIELDS),
+            Err("record 2: missing field 'value'".to_string())
+        );
+        assert!(parse_ndjson("\n\n", &FIELDS).is_err());
+    }
+}
@@ -24,6 +24,7 @@
 //!                "fourteenth", "fifteenth", "sixteenth",
 //!            ],
 //!        },
+//!        ..ChartOptions::default()
 //!    },
 //!);
 //!println!("{chart}");
@@ -55,12 +56,16 @@
 //!  5: sixth        11: twelfth
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(unused_lifetimes, missing_docs)]
 
-use colored::Colorize;
+extern crate alloc;
+
+use canvas::{Canvas, Cell, Color, Role, Style, Swatch};
 
 /// Parameters for creating a `Chart`.
 pub mod params {
+    use alloc::vec::Vec;
 
     #[derive(Debug)]
     #[allow(missing_docs)]
@@ -84,6 +89,188 @@ pub mod params {
         Portrait { labels: &'a [&'a str] },
     }
 
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum XAxis {
+        /// Numeric offsets, which refer to the label table of `DisplayMode::Portrait`.
+        Offsets,
+        /// The labels of `DisplayMode::Portrait`, written below each bar in place of the
+        /// label table. Labels which are too wide to fit below a bar are arranged according
+        /// to `LabelFit`.
+        Labels(LabelFit),
+    }
+
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum LabelFit {
+        /// Write each label downwards, one character per line.
+        Vertical,
+        /// Spread labels over as many lines as needed to avoid overlap.
+        Staggered,
+        /// Skip labels so that every `k`th one fits on a single line.
+        EveryNth,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(missing_docs)]
+    pub enum LabelTruncation {
+        /// Replace the end of a label with `…`.
+        End,
+        /// Replace the middle of a label with `…`, keeping its start and end.
+        Middle,
+        /// Replace the start of a label with `…`.
+        Start,
+        /// Abbreviate the directories of a path separated by `/` to their first character,
+        /// then omit them, keeping the file name. Labels without directories lose their start.
+        Path,
+        /// Continue a label on as many lines as needed, breaking lines between words
+        /// where possible.
+        Wrap,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(missing_docs)]
+    pub enum SortKey {
+        /// The values of `data`.
+        Primary,
+        /// The values of `compare.data`.
+        Comparison,
+        /// The values of `compare.data` minus the values of `data`.
+        Difference,
+        /// The labels of `DisplayMode::Portrait`.
+        Label,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(missing_docs)]
+    pub enum SortOrder {
+        Ascending,
+        Descending,
+    }
+
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub struct Sort {
+        pub key: SortKey,
+        pub order: SortOrder,
+    }
+
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum LimitEnd {
+        /// Keep the bars with the largest values of the sort key.
+        Top,
+        /// Keep the bars with the smallest values of the sort key.
+        Bottom,
+    }
+
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub struct Limit {
+        /// Which bars to keep, ranked by the key of `options.sort`, or by `data` if unsorted.
+        pub end: LimitEnd,
+        /// How many bars to keep, at least 1.
+        pub count: usize,
+        /// Add a bar labeled "others" after the kept bars, holding the sum of the rest.
+        pub others: bool,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(missing_docs)]
+    pub enum Rounding {
+        /// Round down, so that a bar is never taller than its value.
+        Floor,
+        /// Round to the closest step.
+        Nearest,
+        /// Round up, so that a bar is never shorter than its value.
+        Ceil,
+    }
+
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Normalize {
+        /// Display each value as a share of the sum of its series.
+        Total,
+        /// Display each value as a share of the largest value in its series.
+        Max,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub enum Series {
+        /// `data`
+        Primary,
+        /// `compare.data`
+        Comparison,
+    }
+
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum ReferenceLine {
+        /// A fixed value, such as a budget, drawn with `─`.
+        Threshold(u32),
+        /// The mean of `data`, drawn with `┄`.
+        Mean,
+        /// The median of `data`, drawn with `╌`.
+        Median,
+        /// A fixed value for one series, drawn with `·` through the bars of that series only.
+        Target(Series, u32),
+    }
+
+    #[allow(missing_docs)]
+    pub enum Highlight<'a> {
+        None,
+        /// Offsets of `data` to highlight, before sorting. Each should be less than the
+        /// length of `data`.
+        Indices(&'a [usize]),
+        /// Highlight bars for which this returns `true`, given the value of `data` and the label.
+        Predicate(&'a dyn Fn(u32, Option<&str>) -> bool),
+    }
+
+    impl<'a> core::fmt::Debug for Highlight<'a> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Highlight::None => write!(f, "None"),
+                Highlight::Indices(indices) => f.debug_tuple("Indices").field(indices).finish(),
+                Highlight::Predicate(_) => write!(f, "Predicate(..)"),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(missing_docs)]
+    pub enum Statistic {
+        Count,
+        Sum,
+        Min,
+        Max,
+        Mean,
+        Median,
+        StdDev,
+        /// Number of values equal to 0.
+        Zeros,
+        /// Number of values indicated by `🢁` or `🢃`.
+        Outliers,
+        /// Difference between the sum of `compare.data` and the sum of `data`.
+        Delta,
+    }
+
+    impl Statistic {
+        /// Every statistic, in the order they are displayed in by the CLI.
+        pub const ALL: [Statistic; 10] = [
+            Statistic::Count,
+            Statistic::Sum,
+            Statistic::Min,
+            Statistic::Max,
+            Statistic::Mean,
+            Statistic::Median,
+            Statistic::StdDev,
+            Statistic::Zeros,
+            Statistic::Outliers,
+            Statistic::Delta,
+        ];
+    }
+
     #[derive(Debug)]
     #[allow(missing_docs)]
     pub struct ChartOptions<'a> {
@@ -93,6 +280,44 @@ pub mod params {
         pub view: ViewPreference,
         /// Determines how space surrounding the chart is used.
         pub display: DisplayMode<'a>,
+        /// Determines what is written below the bars.
+        pub x_axis: XAxis,
+        /// Determines the order of the bars. The order of `data` is kept if `None`.
+        pub sort: Option<Sort>,
+        /// Determines which bars are kept, if not all of them.
+        pub limit: Option<Limit>,
+        /// The value which fills the chart, instead of the largest one. Larger values are
+        /// indicated by `🢁`. With `normalize`, this is converted like a value of `data`.
+        pub y_max: Option<u32>,
+        /// The value at the bottom of the chart, instead of 0. Smaller values are indicated
+        /// by `🢃`. With `normalize`, this is converted like a value of `data`.
+        pub y_min: Option<u32>,
+        /// Determines how the height of each bar is rounded to a step, 1/8 of a line.
+        pub rounding: Rounding,
+        /// Rescale `data` and `compare.data` independently, so that series with different
+        /// totals can be compared. Values are indicated as percentages.
+        pub normalize: Option<Normalize>,
+        /// Statistics to display below the chart, for each series.
+        pub summary: &'a [Statistic],
+        /// Horizontal lines drawn through the empty space of the chart, and tagged next to it.
+        /// Lines outside of the displayed range are indicated by `🢁` or `🢃` next to the chart.
+        pub reference_lines: &'a [ReferenceLine],
+        /// Bars to draw attention to. These are drawn in a distinct color, and their offsets
+        /// and labels are marked.
+        pub highlight: Highlight<'a>,
+        /// Written above the chart.
+        pub title: Option<&'a str>,
+        /// The unit of the values, such as "words", written above the tick labels.
+        pub y_caption: Option<&'a str>,
+        /// Written below the offsets or labels, centered under the bars.
+        pub x_caption: Option<&'a str>,
+        /// Explain the colors and markers which appear in the chart, below it.
+        pub legend: bool,
+        /// The width of a label, in columns of the terminal. Wider labels are shortened
+        /// according to `label_truncation`.
+        pub label_width: usize,
+        /// Determines how labels wider than `label_width` are shortened.
+        pub label_truncation: LabelTruncation,
     }
 
     impl<'a> Default for ChartOptions<'a> {
@@ -101,7 +326,57 @@ pub mod params {
                 height: 8,
                 view: ViewPreference::Top,
                 display: DisplayMode::Compact,
+                x_axis: XAxis::Offsets,
+                sort: None,
+                limit: None,
+                y_max: None,
+                y_min: None,
+                rounding: Rounding::Floor,
+                normalize: None,
+                summary: &[],
+                reference_lines: &[],
+                highlight: Highlight::None,
+                title: None,
+                y_caption: None,
+                x_caption: None,
+                legend: false,
+                label_width: 12,
+                label_truncation: LabelTruncation::End,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[allow(missing_docs)]
+    pub enum Join {
+        /// Keep only the labels found in both series.
+        Inner,
+        /// Keep the labels found in either series. A value missing from one of them is
+        /// indicated by `∅`.
+        Outer,
+    }
+
+    impl Join {
+        /// Pair the values of two labeled series which have the same label, each with the
+        /// first unpaired occurrence of its label. Labels are kept in order of appearance,
+        /// `primary` first, with the value of each series if it has one.
+        pub fn pair<'l>(
+            self,
+            primary: &[(&'l str, u32)],
+            compare: &[(&'l str, u32)],
+        ) -> Vec<(&'l str, Option<u32>, Option<u32>)> {
+            let mut rows: Vec<(&'l str, Option<u32>, Option<u32>)> =
+                primary.iter().map(|&(l, v)| (l, Some(v), None)).collect();
+            for &(label, value) in compare {
+                match rows.iter_mut().find(|(l, _, c)| *l == label && c.is_none()) {
+                    Some(row) => row.2 = Some(value),
+                    None => rows.push((label, None, Some(value))),
+                }
             }
+            if self == Join::Inner {
+                rows.retain(|(_, p, c)| p.is_some() && c.is_some());
+            }
+            rows
         }
     }
 
@@ -113,27 +388,55 @@ pub mod params {
     }
 }
 
+pub mod boxplot;
+pub mod canvas;
+// tests link `std`, whose float methods take the place of `float::Float`
+#[cfg(any(not(feature = "std"), test))]
+#[cfg_attr(test, allow(dead_code))]
+mod float;
+#[cfg(feature = "png")]
+mod font;
+pub mod grid;
+#[cfg(feature = "png")]
+pub mod image;
+pub mod markdown;
+pub mod stats;
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::cmp::Ordering;
+#[cfg(not(any(feature = "std", test)))]
+use float::Float;
 use params::*;
+use stats::Summary;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Display a slice of up to 100 `u32` values.
 pub struct Chart<'a> {
-    data: &'a [u32],
-    compare: Option<ChartComparison<'a>>,
+    data: Cow<'a, [u32]>,
+    compare: Option<Cow<'a, [u32]>>,
+    labels: Option<Cow<'a, [&'a str]>>,
+    // the series which add up to `data`, if stacked
+    stack: Option<Vec<Cow<'a, [u32]>>>,
+    // whether each bar is highlighted according to `options.highlight`
+    highlighted: Vec<bool>,
+    // whether the value of each series is missing from a joined chart
+    missing: Vec<(bool, bool)>,
+    // `data` and `compare` rescaled according to `options.normalize`
+    normalized: Option<(Vec<u32>, Option<Vec<u32>>)>,
     options: ChartOptions<'a>,
 }
 
 impl<'a> Chart<'a> {
     /// `data` and `compare.data` should have the same length.
+    /// `data` may contain more than 100 values if `options.limit` keeps no more than that.
     pub fn new(
         data: &'a [u32],
         compare: Option<ChartComparison<'a>>,
         options: ChartOptions<'a>,
     ) -> Self {
-        assert!(
-            (1..=100).contains(&data.len()),
-            // supports charts up to 200 characters in width
-            "data should contain no more than 100 values"
-        );
         if let Some(ref compare) = compare {
             assert_eq!(
                 compare.data.len(),
@@ -141,23 +444,298 @@ impl<'a> Chart<'a> {
                 "compare data length should equal primary data length",
             )
         }
-        if let DisplayMode::Portrait { labels } = options.display {
+
+        Self::build(
+            Cow::Borrowed(data),
+            compare.map(|c| Cow::Borrowed(c.data)),
+            None,
+            display_labels(&options),
+            None,
+            options,
+        )
+    }
+
+    /// Display two labeled series, pairing the values which have the same label as
+    /// `Join::pair` does. Labels are displayed as if given by `DisplayMode::Portrait`
+    /// in place of `options.display`.
+    /// A missing value is treated as 0, but indicated by `∅` instead of `⨯`.
+    /// Returns `None` if no label is left, as when `join` is `Join::Inner` and the
+    /// series have no label in common.
+    pub fn joined(
+        primary: &[(&'a str, u32)],
+        compare: &[(&'a str, u32)],
+        join: Join,
+        options: ChartOptions<'a>,
+    ) -> Option<Self> {
+        let rows = join.pair(primary, compare);
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(Self::build(
+            Cow::Owned(rows.iter().map(|(_, p, _)| p.unwrap_or(0)).collect()),
+            Some(Cow::Owned(
+                rows.iter().map(|(_, _, c)| c.unwrap_or(0)).collect(),
+            )),
+            None,
+            Some(Cow::Owned(rows.iter().map(|(l, _, _)| *l).collect())),
+            Some(
+                rows.iter()
+                    .map(|(_, p, c)| (p.is_none(), c.is_none()))
+                    .collect(),
+            ),
+            options,
+        ))
+    }
+
+    /// Display the sums of several series, drawing each series on top of the one before it
+    /// in a different color. Each of `layers` should have the same length.
+    pub fn stacked(layers: &[&'a [u32]], options: ChartOptions<'a>) -> Self {
+        assert!(
+            !layers.is_empty(),
+            "layers should contain at least 1 series"
+        );
+        let len = layers[0].len();
+        assert!(
+            layers.iter().all(|l| l.len() == len),
+            "layer lengths should be equal",
+        );
+
+        let totals = (0..len)
+            .map(|i| layers.iter().fold(0u32, |sum, l| sum.saturating_add(l[i])))
+            .collect::<Vec<_>>();
+
+        Self::build(
+            Cow::Owned(totals),
+            None,
+            Some(layers.iter().map(|&l| Cow::Borrowed(l)).collect()),
+            display_labels(&options),
+            None,
+            options,
+        )
+    }
+
+    fn build(
+        data: Cow<'a, [u32]>,
+        compare: Option<Cow<'a, [u32]>>,
+        stack: Option<Vec<Cow<'a, [u32]>>>,
+        labels: Option<Cow<'a, [&'a str]>>,
+        missing: Option<Vec<(bool, bool)>>,
+        options: ChartOptions<'a>,
+    ) -> Self {
+        assert!(!data.is_empty(), "data should contain at least 1 value");
+        if let Some(limit) = &options.limit {
+            assert!(limit.count > 0, "limit count should be at least 1");
+        }
+        if let Some(labels) = &labels {
             assert_eq!(
                 labels.len(),
                 data.len(),
                 "label count should equal data length",
             );
         }
+        if let (XAxis::Labels(_), None) = (&options.x_axis, &labels) {
+            panic!("x-axis labels require DisplayMode::Portrait");
+        }
+        if let Highlight::Indices(indices) = &options.highlight {
+            assert!(
+                indices.iter().all(|&i| i < data.len()),
+                "highlight indices should be less than data length"
+            );
+        }
+
+        let order = arrange(&data, compare.as_deref(), labels.as_deref(), &options);
+        let (data, compare, stack, labels, missing) = match &order {
+            Some(order) => {
+                let others =
+                    options.limit.as_ref().is_some_and(|l| l.others) && order.len() < data.len();
+                let select = |values: &[u32]| -> Cow<'a, [u32]> {
+                    let mut selected = order.iter().map(|&i| values[i]).collect::<Vec<_>>();
+                    if others {
+                        let rest = (0..values.len())
+                            .filter(|i| !order.contains(i))
+                            .fold(0u32, |sum, i| sum.saturating_add(values[i]));
+                        selected.push(rest);
+                    }
+                    Cow::Owned(selected)
+                };
+                (
+                    select(&data),
+                    compare.map(|c| select(&c)),
+                    stack.map(|layers| layers.iter().map(|l| select(l)).collect()),
+                    labels.map(|l| {
+                        let mut selected = order.iter().map(|&i| l[i]).collect::<Vec<_>>();
+                        if others {
+                            selected.push("others");
+                        }
+                        Cow::Owned(selected)
+                    }),
+                    missing.map(|m| {
+                        let mut selected = order.iter().map(|&i| m[i]).collect::<Vec<_>>();
+                        if others {
+                            selected.push((false, false));
+                        }
+                        selected
+                    }),
+                )
+            }
+            None => (data, compare, stack, labels, missing),
+        };
+        let missing = missing.unwrap_or_else(|| vec![(false, false); data.len()]);
+
+        assert!(
+            data.len() <= 100,
+            // supports charts up to 200 characters in width
+            "data should contain no more than 100 values"
+        );
+
+        let highlighted = (0..data.len())
+            .map(|i| match &options.highlight {
+                Highlight::None => false,
+                Highlight::Indices(indices) => {
+                    // refer to the original offset, which the "others" bar does not have
+                    let offset = match &order {
+                        Some(order) => order.get(i).copied(),
+                        None => Some(i),
+                    };
+                    offset.is_some_and(|offset| indices.contains(&offset))
+                }
+                Highlight::Predicate(predicate) => {
+                    predicate(data[i], labels.as_deref().map(|l| l[i]))
+                }
+            })
+            .collect();
+
+        let normalized = options.normalize.as_ref().map(|method| {
+            (
+                normalize(&data, method),
+                compare.as_deref().map(|c| normalize(c, method)),
+            )
+        });
 
         Self {
             data,
             compare,
+            labels,
+            stack,
+            highlighted,
+            missing,
+            normalized,
             options,
         }
     }
 
-    fn render(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let (data_steps, cmp_data_steps) = self.scale_to_steps();
+    /// The values which determine the height of each bar, as displayed: `data()` and
+    /// `compare_data()`, rescaled according to `options.normalize`. A comparison bar is
+    /// colored as regressed if its measurement is larger than that of its primary bar.
+    pub fn measurements(&self) -> (&[u32], Option<&[u32]>) {
+        match &self.normalized {
+            Some((data, compare)) => (data, compare.as_deref()),
+            None => (&self.data, self.compare.as_deref()),
+        }
+    }
+
+    /// Convert a value of `series` to a measurement, in the same way as `measurements`.
+    fn measure(&self, value: u32, series: Series) -> u32 {
+        let values = match (series, self.compare.as_deref()) {
+            (Series::Comparison, Some(c)) => c,
+            _ => &self.data,
+        };
+        match &self.options.normalize {
+            Some(method) => normalize_value(value, normalize_whole(values, method)),
+            None => value,
+        }
+    }
+
+    /// Format a measurement for display next to the chart.
+    fn tick_label(&self, measurement: u32) -> String {
+        match self.options.normalize {
+            Some(_) => format!(
+                "{:.1}%",
+                measurement as f64 * 100.0 / PARTS_PER_MILLION as f64
+            ),
+            None => measurement.to_string(),
+        }
+    }
+
+    /// The values of `data` as displayed, after sorting and limiting.
+    pub fn data(&self) -> &[u32] {
+        &self.data
+    }
+
+    /// The values of `compare.data` as displayed, if any.
+    pub fn compare_data(&self) -> Option<&[u32]> {
+        self.compare.as_deref()
+    }
+
+    /// The label of each bar as displayed, if any.
+    pub fn labels(&self) -> Option<&[&'a str]> {
+        self.labels.as_deref()
+    }
+
+    /// The bars indicated by `🢁` or `🢃` because their values are outside of the displayed
+    /// range, by offset and series.
+    pub fn outliers(&self) -> Vec<(usize, Series)> {
+        let (data_steps, cmp_data_steps) = self.scale_to_steps(&self.scale());
+        let mut outliers = vec![];
+        for i in 0..data_steps.len() {
+            if data_steps[i] < 0 {
+                outliers.push((i, Series::Primary));
+            }
+            if cmp_data_steps.as_ref().is_some_and(|steps| steps[i] < 0) {
+                outliers.push((i, Series::Comparison));
+            }
+        }
+        outliers
+    }
+
+    /// Lay out the chart as a grid of styled characters.
+    pub fn canvas(&self) -> Canvas {
+        let mut canvas = Canvas::default();
+        self.draw(&mut canvas);
+        canvas
+    }
+
+    /// Lay out the chart on `canvas`, in place of what it held. Its buffers are kept, so
+    /// drawing one chart after another on the same canvas allocates very little.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        self.render(&self.scale(), canvas)
+    }
+
+    /// Append the chart to `out`, as written by `Display`, laid out on `canvas`. Styles
+    /// are written as escape sequences directly, and both `canvas` and `out` can be
+    /// reused from one chart to the next.
+    pub fn render_into(&self, canvas: &mut Canvas, out: &mut String) {
+        self.draw(canvas);
+        canvas
+            .write_text(out)
+            .expect("writing to a String doesn't fail");
+    }
+
+    /// Write the chart to `out`, as written by `Display`, laid out on `canvas`, which can
+    /// be reused from one chart to the next. The text is written to `out` as it's
+    /// formatted, in small pieces, so an unbuffered `out` should be wrapped in a
+    /// `BufWriter`.
+    #[cfg(feature = "std")]
+    pub fn write_to(
+        &self,
+        canvas: &mut Canvas,
+        out: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        self.draw(canvas);
+        canvas.write_io(out)
+    }
+
+    fn render(&self, scale: &Scale, c: &mut Canvas) {
+        let (data, cmp_data) = self.measurements();
+        // a cell per bar and a few for the sidebar, on each layer and the rows below
+        let bar_width = match cmp_data {
+            Some(_) => 3,
+            None => 1,
+        };
+        let rows = self.options.height as usize + 4;
+        c.reset(rows * (data.len() * bar_width + 16), rows);
+        let (data_steps, cmp_data_steps) = self.scale_to_steps(scale);
         let steps_zipped: Vec<(&i16, Option<&i16>)> = match cmp_data_steps {
             Some(ref cmp_data_steps) => data_steps
                 .iter()
@@ -167,35 +745,13 @@ impl<'a> Chart<'a> {
             None => data_steps.iter().map(|a| (a, None)).collect(),
         };
 
-        // determine the largest and smallest values displayed,
-        // for indicating the range of values next to the chart
-        let find_min_max = |data: &[u32], steps: &Vec<i16>| -> (u32, u32) {
-            let mut min = u32::MAX;
-            let mut max = u32::MIN;
-            for i in 0..data.len() {
-                if steps[i] > 0 {
-                    if data[i] < min {
-                        min = data[i];
-                    }
-                    if data[i] > max {
-                        max = data[i];
-                    }
-                }
-            }
-            (min, max)
-        };
-        let (mut min_visible, mut max_visible) = find_min_max(self.data, &data_steps);
-        if let (Some(c), Some(steps)) = (self.compare.as_ref(), &cmp_data_steps) {
-            let (min_visible_cmp, max_visible_cmp) = find_min_max(c.data, steps);
-            min_visible = std::cmp::min(min_visible, min_visible_cmp);
-            max_visible = std::cmp::max(max_visible, max_visible_cmp);
-        }
+        // the range of values, indicated next to the chart
+        let (min_visible, max_visible) = (scale.min_visible, scale.max_measurement);
 
         let get_print_char = |layer_num: u16, steps_count: i16| -> char {
             // display heights are calculated in terms of "steps"
             // a step is the height of this character: ▁
             // each layer corresponds to a line of text, or 8 steps
-            let chars = " ▁▂▃▄▅▆▇█🢃🢁⨯".chars().collect::<Vec<_>>();
 
             // determine the range of steps corresponding to this layer
             // examples: (16,24] (8,16] (0,8]
@@ -203,214 +759,1087 @@ impl<'a> Chart<'a> {
             let print_steps_end = ((layer_num + 1) * 8) as i16;
 
             match steps_count {
-                0 if layer_num == 0 => chars[11],
-                -1 if layer_num == 0 => chars[9],
-                -2 => chars[10],
-                below if below <= print_steps_start => chars[0],
-                above if above > print_steps_end => chars[8],
-                value => chars[(value - print_steps_start) as usize],
+                0 if layer_num == 0 => GLYPHS[11],
+                -1 if layer_num == 0 => GLYPHS[9],
+                -2 => GLYPHS[10],
+                below if below <= print_steps_start => GLYPHS[0],
+                above if above > print_steps_end => GLYPHS[8],
+                value => GLYPHS[(value - print_steps_start) as usize],
+            }
+        };
+
+        // determine what each character of a bar represents
+        let bar_cell = |glyph: char, offset: usize, series: Series, style: Style| -> Cell {
+            let role = match glyph {
+                ' ' => Role::Blank,
+                '⨯' => Role::ZeroMarker { offset, series },
+                '🢃' | '🢁' => Role::OutlierMarker { offset, series },
+                '∅' => Role::MissingMarker { offset, series },
+                _ => Role::Bar {
+                    offset,
+                    series,
+                    layer: None,
+                },
+            };
+            Cell { glyph, role, style }
+        };
+        // the cell of a stacked bar, drawing the layer `k`
+        let layer_cell = |glyph: char, offset: usize, k: usize, style: Style| -> Cell {
+            let mut cell = bar_cell(glyph, offset, Series::Primary, style);
+            if let Role::Bar { layer, .. } = &mut cell.role {
+                *layer = Some(k);
+            }
+            cell
+        };
+
+        // determine where each series of a stacked bar ends, in terms of steps,
+        // by dividing the steps of the bar in proportion to the series values
+        let stack_bounds: Option<Vec<Vec<i16>>> = self.stack.as_ref().map(|layers| {
+            (0..self.data.len())
+                .map(|i| {
+                    let (total, steps) = (self.data[i] as i64, data_steps[i] as i64);
+                    let mut sum = 0i64;
+                    layers
+                        .iter()
+                        .map(|l| {
+                            sum += l[i] as i64;
+                            match total {
+                                0 => 0,
+                                total => ((steps * sum + total / 2) / total) as i16,
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+
+        let get_stacked_cell = |layer_num: u16, bounds: &[i16], offset: usize| -> Cell {
+            // draw the series which covers the bottom of this layer
+            let start = (layer_num * 8) as i16;
+            let Some(k) = bounds.iter().position(|&b| b > start) else {
+                return Cell::BLANK;
+            };
+            let color = |k: usize| STACK_COLORS[k % STACK_COLORS.len()];
+
+            let end = start + 8;
+            if bounds[k] >= end {
+                let glyph = get_print_char(layer_num, bounds[k]);
+                return layer_cell(glyph, offset, k, Style::fg(color(k)));
             }
+            // the series ends within this layer. if another series covers the top of the
+            // layer, use its color as the background, so that both remain visible.
+            // any others ending within this layer are hidden.
+            let (glyph, layer, style) = match bounds[k + 1..].iter().position(|&b| b >= end) {
+                Some(j) => (
+                    get_print_char(layer_num, bounds[k]),
+                    k,
+                    Style::fg(color(k)).on(color(k + 1 + j)),
+                ),
+                // the stack ends within this layer: draw its top in the color of the last
+                // series which is part of it
+                None => {
+                    let top = bounds[bounds.len() - 1];
+                    let last = (k..bounds.len())
+                        .rev()
+                        .find(|&j| j == k || bounds[j] > bounds[j - 1])
+                        .unwrap();
+                    (get_print_char(layer_num, top), last, Style::fg(color(last)))
+                }
+            };
+            layer_cell(glyph, offset, layer, style)
         };
 
         // determine the character width to use for each bar based on
         // how many characters are required to label it with a numeric offset
         let bar_width_chars = if self.data.len() <= 10 { 1 } else { 2 };
 
-        let tick_spacer = max_visible
-            .to_string()
-            .chars()
-            .map(|_| " ")
-            .collect::<String>();
+        // determine the layer in which each reference line is drawn,
+        // or whether it is above or below the displayed range
+        let reference_marks = self
+            .options
+            .reference_lines
+            .iter()
+            .map(|line| {
+                let (glyph, series, measurement) = match *line {
+                    ReferenceLine::Threshold(v) => ('─', None, self.measure(v, Series::Primary)),
+                    ReferenceLine::Mean => ('┄', None, Summary::of(data).mean.round() as u32),
+                    ReferenceLine::Median => ('╌', None, Summary::of(data).median.round() as u32),
+                    ReferenceLine::Target(series, v) => {
+                        ('·', Some(series), self.measure(v, series))
+                    }
+                };
+                let layer = match scale.step_count(measurement) {
+                    -2 => Err(Ordering::Greater),
+                    steps if steps > scale.max_step_count as i16 => Err(Ordering::Greater),
+                    steps if steps <= 0 => Err(Ordering::Less),
+                    steps => Ok(((steps - 1) / 8) as u16),
+                };
+                ReferenceMark {
+                    glyph,
+                    series,
+                    label: self.tick_label(measurement),
+                    layer,
+                }
+            })
+            .collect::<Vec<_>>();
+        let get_line_cell = |layer_num: u16, series: Option<Series>| -> Option<Cell> {
+            reference_marks
+                .iter()
+                .find(|m| m.layer == Ok(layer_num) && (m.series.is_none() || m.series == series))
+                .map(|m| Cell {
+                    glyph: m.glyph,
+                    role: Role::ReferenceLine,
+                    style: Style::fg(Color::Yellow),
+                })
+        };
+        // reference lines are only drawn through empty space
+        let or_line_cell =
+            |cell: Cell, layer_num: u16, series: Option<Series>| match cell.glyph == ' ' {
+                true => get_line_cell(layer_num, series).unwrap_or(cell),
+                false => cell,
+            };
+
+        // nothing may be visible if the range is fixed
+        let (min_visible, max_visible) = (
+            min_visible.map_or(String::new(), |m| self.tick_label(m)),
+            self.tick_label(max_visible),
+        );
+        let tick_label_width = reference_marks
+            .iter()
+            .filter(|m| m.layer.is_ok())
+            .map(|m| m.label.len())
+            .chain([min_visible.len(), max_visible.len()])
+            .max()
+            .unwrap();
+        // reference lines are tagged in a column next to the tick labels
+        let tag_width = match reference_marks.is_empty() {
+            true => 0,
+            false => 1,
+        };
+        let tick_spacer = " ".repeat(tick_label_width + tag_width);
+
+        if let Some(title) = self.options.title {
+            let bold = Style {
+                bold: true,
+                ..Style::PLAIN
+            };
+            c.push_str(title, Role::Title, bold);
+            c.end_row();
+        }
+        if let Some(caption) = self.options.y_caption {
+            let caption = format!("{caption:>0$}", tick_spacer.len());
+            c.push_str(&caption, Role::Caption, Style::PLAIN);
+            c.end_row();
+        }
 
-        let mut write_layer = |layer_num: u16| -> std::fmt::Result {
+        // write layers
+        for layer_num in (0..self.options.height).rev() {
             // write left sidebar
-            if layer_num == self.options.height - 1 {
-                write!(f, "{max_visible}│")?;
-            } else if layer_num == 0 {
-                let gap = (0..tick_spacer.len() - min_visible.to_string().len())
-                    .map(|_| " ")
-                    .collect::<String>();
-                write!(f, "{gap}{min_visible}│")?;
-            } else {
-                write!(f, "{tick_spacer}│")?;
+            let mark = reference_marks.iter().find(|m| m.layer == Ok(layer_num));
+            let (is_top, is_bottom) = (layer_num == self.options.height - 1, layer_num == 0);
+            // a reference line is labeled with its own value, even in place of the top or
+            // bottom tick, as the tag next to it would otherwise claim the tick's value
+            let tick_label = match mark {
+                Some(mark) => &mark.label,
+                None if is_top => &max_visible,
+                None if is_bottom => &min_visible,
+                None => "",
             };
+            let tick_label = format!("{tick_label:>tick_label_width$}");
+            c.push_str(&tick_label, Role::Sidebar, Style::PLAIN);
+            if tag_width > 0 {
+                let hidden = |ord| reference_marks.iter().any(|m| m.layer == Err(ord));
+                let tag = match mark {
+                    Some(mark) => mark.glyph,
+                    None if is_top && hidden(Ordering::Greater) => '🢁',
+                    None if is_bottom && hidden(Ordering::Less) => '🢃',
+                    None => ' ',
+                };
+                c.push(Cell {
+                    glyph: tag,
+                    role: Role::Sidebar,
+                    style: Style::fg(Color::Yellow),
+                });
+            }
+            c.push_str("│", Role::Sidebar, Style::PLAIN);
 
             // write a layer of each bar
             for (i, &(&pri_steps, cmp_steps)) in steps_zipped.iter().enumerate() {
-                let pri_char = get_print_char(layer_num, pri_steps).to_string();
+                let (pri_missing, cmp_missing) = self.missing[i];
+                let pri_glyph = match pri_missing && layer_num == 0 {
+                    true => '∅',
+                    false => get_print_char(layer_num, pri_steps),
+                };
                 match cmp_steps {
                     None => {
-                        let pri_char = if i % 2 == 0 {
-                            pri_char.bright_white()
-                        } else {
-                            pri_char.white()
+                        let pri_cell = match &stack_bounds {
+                            Some(bounds) if pri_steps > 0 => {
+                                get_stacked_cell(layer_num, &bounds[i], i)
+                            }
+                            _ => {
+                                let color = match i {
+                                    _ if self.highlighted[i] => Color::BrightCyan,
+                                    _ if i % 2 == 0 => Color::BrightWhite,
+                                    _ => Color::White,
+                                };
+                                bar_cell(pri_glyph, i, Series::Primary, Style::fg(color))
+                            }
                         };
+                        let pri_cell = or_line_cell(pri_cell, layer_num, Some(Series::Primary));
                         for _ in 0..bar_width_chars {
-                            write!(f, "{pri_char}")?;
+                            c.push(pri_cell);
                         }
                     }
                     // if comparison, each bar only needs to be 1 character wide
                     // for offsets to fit at the bottom
                     Some(&cmp_steps) => {
-                        write!(f, "{}", pri_char.bright_white())?;
+                        let color = match self.highlighted[i] {
+                            true => Color::BrightCyan,
+                            false => Color::BrightWhite,
+                        };
+                        let pri_cell = bar_cell(pri_glyph, i, Series::Primary, Style::fg(color));
+                        c.push(or_line_cell(pri_cell, layer_num, Some(Series::Primary)));
 
-                        let cmp_char = get_print_char(layer_num, cmp_steps).to_string();
-                        let pri_value = self.data[i];
-                        let cmp_value = self.compare.as_ref().unwrap().data[i];
-                        let cmp_char = if cmp_value <= pri_value {
-                            cmp_char.bright_green()
+                        let cmp_glyph = match cmp_missing && layer_num == 0 {
+                            true => '∅',
+                            false => get_print_char(layer_num, cmp_steps),
+                        };
+                        let pri_value = data[i];
+                        let cmp_value = cmp_data.unwrap()[i];
+                        let color = if cmp_value <= pri_value {
+                            Color::BrightGreen
                         } else {
-                            cmp_char.bright_red()
+                            Color::BrightRed
                         };
-                        write!(f, "{cmp_char} ",)?;
+                        let cmp_cell = bar_cell(cmp_glyph, i, Series::Comparison, Style::fg(color));
+                        c.push(or_line_cell(cmp_cell, layer_num, Some(Series::Comparison)));
+                        c.push(or_line_cell(Cell::BLANK, layer_num, None));
                     }
                 }
             }
 
             // move to layer below
-            writeln!(f)
-        };
-
-        // write layers
-        for layer_num in (0..self.options.height).rev() {
-            write_layer(layer_num)?;
-        }
-
-        // write offsets
-        write!(f, "{tick_spacer} ")?;
-        let mut chart_width = tick_spacer.len() as u16;
-        for i in 0..self.data.len() {
-            write!(f, "{i}")?;
-            let label_width = i.to_string().len() as u16;
-            chart_width += label_width;
-            let offset_width = match (self.compare.is_some(), bar_width_chars) {
-                (false, width) => width,
-                (true, _) => 3,
-            };
-            for _ in label_width..offset_width {
-                write!(f, " ")?;
-                chart_width += 1;
-            }
+            c.end_row();
         }
 
-        if let DisplayMode::Portrait { labels } = self.options.display {
-            writeln!(f)?;
-
-            // split labels into evenly-sized columns
-            // so as to fill horizontal space below the chart
-            // each label is allowed 12 characters before being truncated to fit
-            let col_count = std::cmp::max((chart_width as f32 / 17f32).floor() as usize, 1usize);
-            let col_length = labels.len().div_ceil(col_count);
-            let enumerated_labels = labels.iter().enumerate().collect::<Vec<_>>();
-            let label_cols = enumerated_labels.chunks(col_length).collect::<Vec<_>>();
-            let max_rows = label_cols.iter().map(|c| c.len()).max().unwrap();
-
-            for i in 0..max_rows {
-                for col in &label_cols {
-                    if let Some((offset, label)) = col.get(i) {
-                        write!(
-                            f,
-                            // each column requires 17 characters
-                            "{offset:>2}: {:<12} ",
-                            label.chars().take(12).collect::<String>()
-                        )?;
+        // write offsets, or labels in their place
+        let bar_slot_chars = if self.compare.is_some() {
+            3
+        } else {
+            bar_width_chars
+        };
+        let chart_width = (tick_spacer.len() + self.data.len() * bar_slot_chars) as u16;
+        let write_x_caption = |c: &mut Canvas| {
+            if let Some(caption) = self.options.x_caption {
+                let bars_width = self.data.len() * bar_slot_chars;
+                let indent = tick_spacer.len() + 1 + bars_width.saturating_sub(caption.width()) / 2;
+                c.push_str(&" ".repeat(indent), Role::Blank, Style::PLAIN);
+                c.push_str(caption, Role::Caption, Style::PLAIN);
+                c.end_row();
+            }
+        };
+        let reversed = Style {
+            reversed: true,
+            ..Style::PLAIN
+        };
+        let label_fit = (self.options.label_width, self.options.label_truncation);
+        match (&self.options.x_axis, self.labels.as_deref()) {
+            (XAxis::Labels(fit), Some(labels)) => {
+                let axis = label_axis(labels, fit, bar_slot_chars, label_fit);
+                for row in axis {
+                    c.push_str(&format!("{tick_spacer} "), Role::Blank, Style::PLAIN);
+                    let row_len = row
+                        .iter()
+                        .rposition(|(c, _)| *c != ' ')
+                        .map_or(0, |p| p + 1);
+                    for &(glyph, bar) in row[..row_len].iter().filter(|(c, _)| *c != '\0') {
+                        let (role, style) = match bar {
+                            Some(i) if self.highlighted[i] => (Role::Label(i), reversed),
+                            Some(i) => (Role::Label(i), Style::PLAIN),
+                            None => (Role::Blank, Style::PLAIN),
+                        };
+                        c.push(Cell { glyph, role, style });
                     }
+                    c.end_row();
+                }
+                write_x_caption(c);
+            }
+            (_, labels) => {
+                c.push_str(&format!("{tick_spacer} "), Role::Blank, Style::PLAIN);
+                for i in 0..self.data.len() {
+                    let offset = i.to_string();
+                    let padding = " ".repeat(bar_slot_chars.saturating_sub(offset.len()));
+                    let style = match self.highlighted[i] {
+                        true => reversed,
+                        false => Style::PLAIN,
+                    };
+                    c.push_str(&offset, Role::Offset(i), style);
+                    c.push_str(&padding, Role::Blank, Style::PLAIN);
+                }
+                c.end_row();
+                write_x_caption(c);
+
+                if let Some(labels) = labels {
+                    write_label_table(c, labels, &self.highlighted, chart_width, label_fit);
                 }
-                writeln!(f)?;
             }
-        } else {
-            writeln!(f)?;
         }
 
-        Ok(())
+        self.write_legend(c, &data_steps, cmp_data_steps.as_deref());
+        let mut summary = String::new();
+        // writing to a String does not fail
+        self.write_summary(&mut summary, &data_steps, cmp_data_steps.as_deref())
+            .unwrap();
+        c.push_str(&summary, Role::Summary, Style::PLAIN);
     }
 
-    fn scale_to_steps(&self) -> (Vec<i16>, Option<Vec<i16>>) {
-        // determine the largest possible measurement that can be expressed within
-        // `height` lines, in terms of steps.
-        let max_step_count: u16 = self.options.height * 8;
-
-        // determine the factor by which to scale all measurements,
-        // so that the largest one fills the available vertical space.
-        let all_measurements = self
-            .data
-            .iter()
-            .chain(self.compare.as_ref().map(|c| c.data).unwrap_or(&[]).iter())
-            .filter(|&&m| m > 0);
-        let all_max = all_measurements.clone().max().unwrap();
-        let unit_height_steps: u16 = std::cmp::max(
-            (max_step_count as f32 / *all_max as f32).floor() as u16,
-            1u16,
-        );
-
-        // determine which measurements can not be expressed in terms of steps
-        // without additional scaling
-        let (excessive, unexcessive) = all_measurements.clone().partition::<Vec<&u32>, _>(|&&m| {
-            m > u16::MAX as u32 || m as u16 * unit_height_steps > max_step_count
-        });
-        let low_max = unexcessive.iter().max();
-        let high_max = excessive.iter().max();
+    fn write_legend(&self, c: &mut Canvas, data_steps: &[i16], cmp_data_steps: Option<&[i16]>) {
+        if !self.options.legend {
+            return;
+        }
 
-        // additional scale factor
-        let (show_excessive, scale_factor) = match (&self.options.view, low_max, high_max) {
-            // fit the chart to the largest small value
-            (ViewPreference::Bottom, Some(&&low_max), _)
-            | (ViewPreference::Top, Some(&&low_max), None) => {
-                (false, max_step_count as f32 / low_max as f32)
+        let mut entries: Vec<(char, Style, Role, String)> = vec![];
+        let swatch = |color: Color, swatch: Swatch, meaning: &str| {
+            ('█', Style::fg(color), Role::Swatch(swatch), meaning.into())
+        };
+        match (&self.stack, cmp_data_steps) {
+            (Some(layers), _) => {
+                for k in 0..layers.len() {
+                    let color = STACK_COLORS[k % STACK_COLORS.len()];
+                    entries.push(swatch(color, Swatch::Layer(k), &format!("layer {k}")));
+                }
             }
-            // the fit the chart to the largest large value
-            (ViewPreference::Top, _, Some(&&high_max))
-            | (ViewPreference::Bottom, None, Some(&&high_max)) => {
-                (true, max_step_count as f32 / high_max as f32)
+            (None, Some(_)) => {
+                entries.push(swatch(Color::BrightWhite, Swatch::Primary, "primary"));
+                entries.push(swatch(
+                    Color::BrightGreen,
+                    Swatch::Improved,
+                    "improved (lower)",
+                ));
+                entries.push(swatch(
+                    Color::BrightRed,
+                    Swatch::Regressed,
+                    "regressed (higher)",
+                ));
             }
-            _ => unimplemented!(),
-        };
+            (None, None) => entries.push(swatch(Color::BrightWhite, Swatch::Primary, "primary")),
+        }
+        if self.highlighted.contains(&true) {
+            entries.push(swatch(
+                Color::BrightCyan,
+                Swatch::Highlighted,
+                "highlighted",
+            ));
+        }
+        for line in self.options.reference_lines {
+            let (glyph, name) = match line {
+                ReferenceLine::Threshold(_) => ('─', "threshold"),
+                ReferenceLine::Mean => ('┄', "mean"),
+                ReferenceLine::Median => ('╌', "median"),
+                ReferenceLine::Target(_, _) => ('·', "target"),
+            };
+            entries.push((glyph, Style::fg(Color::Yellow), Role::Legend, name.into()));
+        }
 
-        // convert measurements to step counts, expressed as signed integer
-        // to allow indication of too small (-1) and too large (-2)
-        let measurement_to_step_count = |m: &u32| -> i16 {
-            if *m == 0 {
-                return 0;
-            }
-            match (excessive.is_empty(), show_excessive, excessive.contains(&m)) {
-                // some are excessive and we don't want them and this is one of them
-                (false, false, true) => -2i16,
-                // some are excessive and we want them, but this isn't one of them
-                (false, true, false) => -1i16,
-                // otherwise, use the scale factor
-                _ => {
-                    let step_count = (*m as f32 * scale_factor) as i16;
-                    if step_count == 0 {
-                        // excessive measurement, but still invisible next to max
-                        -1i16
-                    } else {
-                        step_count
-                    }
-                }
+        // markers are only explained if they appear
+        let all_steps = data_steps.iter().chain(cmp_data_steps.unwrap_or(&[]));
+        // missing values are drawn in place of zeros
+        let is_zero = (0..data_steps.len()).any(|i| data_steps[i] == 0 && !self.missing[i].0)
+            || cmp_data_steps
+                .is_some_and(|steps| (0..steps.len()).any(|i| steps[i] == 0 && !self.missing[i].1));
+        let is_missing = self.missing.iter().any(|&(p, c)| p || c);
+        for (appears, marker, meaning) in [
+            (all_steps.clone().any(|&s| s == -2), '🢁', "above range"),
+            (all_steps.clone().any(|&s| s == -1), '🢃', "below range"),
+            (is_zero, '⨯', "zero"),
+            (is_missing, '∅', "missing"),
+        ] {
+            if appears {
+                entries.push((marker, Style::PLAIN, Role::Legend, meaning.into()));
             }
-        };
-        let scaled_data: Vec<i16> = self.data.iter().map(measurement_to_step_count).collect();
-        let scaled_data_cmp: Option<Vec<i16>> = self
-            .compare
-            .as_ref()
-            .map(|c| c.data.iter().map(measurement_to_step_count).collect());
+        }
 
-        (scaled_data, scaled_data_cmp)
+        for (n, (glyph, style, role, meaning)) in entries.into_iter().enumerate() {
+            if n > 0 {
+                c.push_str("  ", Role::Blank, Style::PLAIN);
+            }
+            c.push(Cell { glyph, role, style });
+            c.push_str(&format!(" {meaning}"), Role::Legend, Style::PLAIN);
+        }
+        c.end_row();
     }
-}
 
-impl<'a> std::fmt::Display for Chart<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.render(f)
-    }
-}
+    fn write_summary(
+        &self,
+        f: &mut dyn core::fmt::Write,
+        data_steps: &[i16],
+        cmp_data_steps: Option<&[i16]>,
+    ) -> core::fmt::Result {
+        if self.options.summary.is_empty() {
+            return Ok(());
+        }
 
-#[cfg(test)]
-mod tests {
-    // cargo test -- --nocapture
+        let outliers = |steps: &[i16]| steps.iter().filter(|&&s| s < 0).count();
+        let mut series = vec![(Summary::of(&self.data), outliers(data_steps))];
+        if let (Some(c), Some(steps)) = (self.compare.as_deref(), cmp_data_steps) {
+            series.push((Summary::of(c), outliers(steps)));
+        }
+
+        fn format_float(v: f64) -> String {
+            match v.fract() {
+                0.0 => format!("{v:.0}"),
+                _ => format!("{v:.1}"),
+            }
+        }
+        type Format = fn(&Summary, usize) -> String;
+        let mut rows: Vec<(&str, Vec<String>)> = vec![];
+        for stat in self.options.summary {
+            let (name, format): (&str, Format) = match stat {
+                Statistic::Count => ("count", |s, _| s.count.to_string()),
+                Statistic::Sum => ("sum", |s, _| s.sum.to_string()),
+                Statistic::Min => ("min", |s, _| s.min.to_string()),
+                Statistic::Max => ("max", |s, _| s.max.to_string()),
+                Statistic::Mean => ("mean", |s, _| format_float(s.mean)),
+                Statistic::Median => ("median", |s, _| format_float(s.median)),
+                Statistic::StdDev => ("std dev", |s, _| format_float(s.std_dev)),
+                Statistic::Zeros => ("zeros", |s, _| s.zeros.to_string()),
+                Statistic::Outliers => ("outliers", |_, outliers| outliers.to_string()),
+                Statistic::Delta => {
+                    // only meaningful for a comparison, displayed across both columns
+                    if let [(primary, _), (compare, _)] = series.as_slice() {
+                        let delta = compare.sum as i64 - primary.sum as i64;
+                        let change = match primary.sum {
+                            0 => String::new(),
+                            sum => format!(" ({:+.1}%)", delta as f64 * 100.0 / sum as f64),
+                        };
+                        rows.push(("delta", vec![format!("{delta:+}{change}")]));
+                    }
+                    continue;
+                }
+            };
+            rows.push((name, series.iter().map(|(s, o)| format(s, *o)).collect()));
+        }
+
+        let name_width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        let mut headers = vec![];
+        if series.len() > 1 {
+            headers = vec!["primary", "compare"];
+        }
+        let value_width = |col: usize| {
+            rows.iter()
+                .filter(|(_, values)| values.len() == series.len())
+                .filter_map(|(_, values)| values.get(col).map(|v| v.len()))
+                .chain(headers.get(col).map(|h| h.len()))
+                .max()
+                .unwrap_or(0)
+        };
+
+        if !headers.is_empty() {
+            write!(f, "{:name_width$}", "")?;
+            for (col, header) in headers.iter().enumerate() {
+                write!(f, "  {header:>0$}", value_width(col))?;
+            }
+            writeln!(f)?;
+        }
+        let table_width = (0..series.len())
+            .map(|col| value_width(col) + 2)
+            .sum::<usize>();
+        for (name, values) in &rows {
+            write!(f, "{name:<name_width$}")?;
+            match values.as_slice() {
+                [value] if series.len() > 1 => write!(f, "  {value:>0$}", table_width - 2)?,
+                _ => {
+                    for (col, value) in values.iter().enumerate() {
+                        write!(f, "  {value:>0$}", value_width(col))?;
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+
+    fn scale_to_steps(&self, scale: &Scale) -> (Vec<i16>, Option<Vec<i16>>) {
+        let (data, cmp_data) = self.measurements();
+        let scaled_data: Vec<i16> = data.iter().map(|&m| scale.step_count(m)).collect();
+        let scaled_data_cmp: Option<Vec<i16>> =
+            cmp_data.map(|c| c.iter().map(|&m| scale.step_count(m)).collect());
+
+        (scaled_data, scaled_data_cmp)
+    }
+
+    fn scale(&self) -> Scale {
+        let (data, cmp_data) = self.measurements();
+        let all_measurements = data.iter().chain(cmp_data.unwrap_or(&[]).iter());
+        Scale::fit(
+            all_measurements,
+            self.options.height,
+            &self.options.view,
+            self.y_range(),
+            self.options.rounding,
+        )
+    }
+
+    /// `options.y_min` and `options.y_max`, as measurements.
+    fn y_range(&self) -> (Option<u32>, Option<u32>) {
+        let measure = |v: Option<u32>| v.map(|v| self.measure(v, Series::Primary));
+        (measure(self.options.y_min), measure(self.options.y_max))
+    }
+}
+
+/// Position of a reference line.
+struct ReferenceMark {
+    glyph: char,
+    // the series through which the line is drawn, or all of them
+    series: Option<Series>,
+    label: String,
+    // layer containing the line, or whether it is above or below the displayed range
+    layer: Result<u16, Ordering>,
+}
+
+/// Conversion of measurements to step counts.
+struct Scale {
+    max_step_count: u16,
+    unit_height_steps: u16,
+    // whether some measurements can not be expressed without additional scaling
+    any_excessive: bool,
+    // whether the additional scaling is applied to show those measurements
+    show_excessive: bool,
+    // the measurement which fills the available vertical space
+    max_measurement: u32,
+    // the measurement at the bottom of the chart, if the range is fixed
+    min_measurement: u32,
+    // whether the range is fixed rather than fit to the measurements
+    fixed: bool,
+    rounding: Rounding,
+    // the smallest of the measurements displayed, which labels the bottom of the chart
+    min_visible: Option<u32>,
+}
+
+impl Scale {
+    /// Fit `height` lines to `measurements`, according to `view`,
+    /// or to the range of `y_range`, `(y_min, y_max)`, if either is given.
+    fn fit<'m>(
+        measurements: impl Iterator<Item = &'m u32> + Clone,
+        height: u16,
+        view: &ViewPreference,
+        y_range: (Option<u32>, Option<u32>),
+        rounding: Rounding,
+    ) -> Self {
+        let mut scale = Self::fit_range(measurements.clone(), height, view, y_range, rounding);
+        scale.min_visible = measurements
+            .filter(|&&m| scale.step_count(m) > 0)
+            .min()
+            .copied();
+        scale
+    }
+
+    fn fit_range<'m>(
+        measurements: impl Iterator<Item = &'m u32> + Clone,
+        height: u16,
+        view: &ViewPreference,
+        (y_min, y_max): (Option<u32>, Option<u32>),
+        rounding: Rounding,
+    ) -> Self {
+        // determine the largest possible measurement that can be expressed within
+        // `height` lines, in terms of steps.
+        let max_step_count: u16 = height * 8;
+
+        if y_min.is_some() || y_max.is_some() {
+            let min_measurement = y_min.unwrap_or(0);
+            let max_measurement = y_max
+                .or_else(|| measurements.max().copied())
+                .unwrap_or(0)
+                .max(min_measurement.saturating_add(1));
+            return Scale {
+                max_step_count,
+                unit_height_steps: 1,
+                any_excessive: false,
+                show_excessive: false,
+                max_measurement,
+                min_measurement,
+                fixed: true,
+                rounding,
+                min_visible: None,
+            };
+        }
+
+        // determine the factor by which to scale all measurements,
+        // so that the largest one fills the available vertical space.
+        let all_measurements = measurements.filter(|&&m| m > 0);
+        let all_max = all_measurements.clone().max();
+        let unit_height_steps: u16 = match all_max {
+            Some(&all_max) => core::cmp::max((max_step_count as u32 / all_max) as u16, 1u16),
+            None => 1,
+        };
+        let mut scale = Scale {
+            max_step_count,
+            unit_height_steps,
+            any_excessive: false,
+            show_excessive: false,
+            max_measurement: 0,
+            min_measurement: 0,
+            fixed: false,
+            rounding,
+            min_visible: None,
+        };
+        if all_max.is_none() {
+            // every measurement is 0
+            return scale;
+        }
+
+        // determine which measurements can not be expressed in terms of steps
+        // without additional scaling
+        let (excessive, unexcessive) = all_measurements
+            .clone()
+            .partition::<Vec<&u32>, _>(|&&m| scale.is_excessive(m));
+        let low_max = unexcessive.iter().max();
+        let high_max = excessive.iter().max();
+
+        // additional scale factor
+        let (show_excessive, max_measurement) = match (view, low_max, high_max) {
+            // fit the chart to the largest small value
+            (ViewPreference::Bottom, Some(&&low_max), _)
+            | (ViewPreference::Top, Some(&&low_max), None) => (false, low_max),
+            // the fit the chart to the largest large value
+            (ViewPreference::Top, _, Some(&&high_max))
+            | (ViewPreference::Bottom, None, Some(&&high_max)) => (true, high_max),
+            _ => unimplemented!(),
+        };
+
+        scale.any_excessive = !excessive.is_empty();
+        scale.show_excessive = show_excessive;
+        scale.max_measurement = max_measurement;
+        scale
+    }
+
+    // scale a measurement within the range to steps, using exact integer arithmetic
+    fn scaled(&self, m: u32) -> i16 {
+        let steps = (m - self.min_measurement) as u64 * self.max_step_count as u64;
+        let span = (self.max_measurement - self.min_measurement) as u64;
+        let step_count = match self.rounding {
+            Rounding::Floor => steps / span,
+            Rounding::Nearest => (steps + span / 2) / span,
+            Rounding::Ceil => steps.div_ceil(span),
+        };
+        step_count as i16
+    }
+
+    fn is_excessive(&self, m: u32) -> bool {
+        m > u16::MAX as u32 || m * self.unit_height_steps as u32 > self.max_step_count as u32
+    }
+
+    // convert measurements to step counts, expressed as signed integer
+    // to allow indication of too small (-1) and too large (-2)
+    fn step_count(&self, m: u32) -> i16 {
+        if m == 0 {
+            return 0;
+        }
+        if self.fixed {
+            return match m {
+                m if m > self.max_measurement => -2i16,
+                m if m < self.min_measurement => -1i16,
+                m => match self.scaled(m) {
+                    // at the bottom of the range
+                    0 => -1i16,
+                    step_count => step_count,
+                },
+            };
+        }
+        match (
+            self.any_excessive,
+            self.show_excessive,
+            self.is_excessive(m),
+        ) {
+            // some are excessive and we don't want them and this is one of them
+            (true, false, true) => -2i16,
+            // some are excessive and we want them, but this isn't one of them
+            (true, true, false) => -1i16,
+            // otherwise, scale to the range
+            _ => {
+                let step_count = self.scaled(m);
+                if step_count == 0 {
+                    // excessive measurement, but still invisible next to max
+                    -1i16
+                } else {
+                    step_count
+                }
+            }
+        }
+    }
+}
+
+// the empty layer, eighths of a layer from ▁ to █, then the markers
+// of a low outlier, a high outlier and a zero value
+const GLYPHS: [char; 12] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█', '🢃', '🢁', '⨯'];
+
+// colors of stacked series, from the bottom up
+const STACK_COLORS: [Color; 9] = [
+    Color::BrightWhite,
+    Color::BrightBlue,
+    Color::BrightYellow,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::Blue,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+// resolution of normalized measurements
+const PARTS_PER_MILLION: u64 = 1_000_000;
+
+/// Rescale values to parts per million of the total or maximum of `values`.
+fn normalize(values: &[u32], method: &Normalize) -> Vec<u32> {
+    let whole = normalize_whole(values, method);
+    values.iter().map(|&v| normalize_value(v, whole)).collect()
+}
+
+fn normalize_whole(values: &[u32], method: &Normalize) -> u64 {
+    match method {
+        Normalize::Total => values.iter().map(|&v| v as u64).sum::<u64>(),
+        Normalize::Max => values.iter().max().map_or(0, |&v| v as u64),
+    }
+}
+
+fn normalize_value(value: u32, whole: u64) -> u32 {
+    match (value, whole) {
+        (0, _) | (_, 0) => 0,
+        // small values should not be indicated as zero
+        (v, whole) => core::cmp::max((v as u64 * PARTS_PER_MILLION + whole / 2) / whole, 1) as u32,
+    }
+}
+
+/// Determine which bars to display and in which order, according to `options.sort` and
+/// `options.limit`. Returns `None` if all bars are displayed in their original order.
+fn arrange(
+    data: &[u32],
+    compare: Option<&[u32]>,
+    labels: Option<&[&str]>,
+    options: &ChartOptions,
+) -> Option<Vec<usize>> {
+    if options.sort.is_none() && options.limit.is_none() {
+        return None;
+    }
+
+    let key = options
+        .sort
+        .as_ref()
+        .map(|s| s.key)
+        .unwrap_or(SortKey::Primary);
+    let key_cmp = |&a: &usize, &b: &usize| -> Ordering {
+        match key {
+            SortKey::Primary => data[a].cmp(&data[b]),
+            SortKey::Comparison => {
+                let compare = compare.expect("sorting by comparison requires compare data");
+                compare[a].cmp(&compare[b])
+            }
+            SortKey::Difference => {
+                let compare = compare.expect("sorting by difference requires compare data");
+                let diff = |i: usize| compare[i] as i64 - data[i] as i64;
+                diff(a).cmp(&diff(b))
+            }
+            SortKey::Label => {
+                let labels = labels.expect("sorting by label requires DisplayMode::Portrait");
+                labels[a].cmp(labels[b])
+            }
+        }
+    };
+
+    let mut order = (0..data.len()).collect::<Vec<_>>();
+
+    if let Some(limit) = &options.limit {
+        // rank the bars, preferring the earlier of equal values
+        let mut ranked = order.clone();
+        match limit.end {
+            LimitEnd::Top => ranked.sort_by(|a, b| key_cmp(b, a)),
+            LimitEnd::Bottom => ranked.sort_by(key_cmp),
+        }
+        ranked.truncate(limit.count);
+        order.retain(|i| ranked.contains(i));
+    }
+
+    if let Some(sort) = &options.sort {
+        match sort.order {
+            SortOrder::Ascending => order.sort_by(key_cmp),
+            SortOrder::Descending => order.sort_by(|a, b| key_cmp(b, a)),
+        }
+    }
+
+    Some(order)
+}
+
+/// The labels of `DisplayMode::Portrait`, if any.
+fn display_labels<'a>(options: &ChartOptions<'a>) -> Option<Cow<'a, [&'a str]>> {
+    match options.display {
+        DisplayMode::Portrait { labels } => Some(Cow::Borrowed(labels)),
+        DisplayMode::Compact => None,
+    }
+}
+
+/// Split labels into evenly-sized columns so as to fill horizontal space below the chart.
+/// Each label is fitted within `width` columns of the terminal.
+fn write_label_table(
+    c: &mut Canvas,
+    labels: &[&str],
+    highlighted: &[bool],
+    chart_width: u16,
+    (width, truncation): (usize, LabelTruncation),
+) {
+    let width = fitting_width(labels, width);
+    // each column requires the label width and 5 characters for the offset and a space
+    let col_chars = width + 5;
+    let col_count = core::cmp::max(chart_width as usize / col_chars, 1usize);
+    let col_length = labels.len().div_ceil(col_count);
+    let fitted = labels
+        .iter()
+        .map(|l| fit_label(l, width, truncation))
+        .collect::<Vec<_>>();
+    let offsets = (0..labels.len()).collect::<Vec<_>>();
+    let label_cols = offsets.chunks(col_length).collect::<Vec<_>>();
+    let max_rows = label_cols.iter().map(|c| c.len()).max().unwrap();
+
+    for i in 0..max_rows {
+        // a wrapped label continues on the lines below its offset
+        let line_count = label_cols
+            .iter()
+            .filter_map(|col| col.get(i).map(|&offset| fitted[offset].len()))
+            .max()
+            .unwrap_or(1);
+        for line in 0..line_count {
+            for col in &label_cols {
+                if let Some(&offset) = col.get(i) {
+                    let text = fitted[offset].get(line).map_or("", |l| l.as_str());
+                    let label = format!("{text}{}", " ".repeat(width - text.width()));
+                    let (mark, style) = match highlighted[offset] {
+                        true => ('*', Style::fg(Color::BrightCyan)),
+                        false => (':', Style::PLAIN),
+                    };
+                    match line {
+                        0 => {
+                            let offset_text = format!("{offset:>2}{mark} ");
+                            c.push_str(&offset_text, Role::Offset(offset), Style::PLAIN);
+                        }
+                        _ => c.push_str("    ", Role::Blank, Style::PLAIN),
+                    }
+                    c.push_str(&label, Role::Label(offset), style);
+                    c.push(Cell::BLANK);
+                }
+            }
+            c.end_row();
+        }
+    }
+}
+
+/// Arrange labels below bars which are `slot_chars` wide, returning the lines of text to write.
+/// Each character is paired with the offset of the bar it labels. A character which is
+/// 2 columns wide is followed by `'\0'` in place of its second column.
+fn label_axis(
+    labels: &[&str],
+    fit: &LabelFit,
+    slot_chars: usize,
+    (width, truncation): (usize, LabelTruncation),
+) -> Vec<Vec<(char, Option<usize>)>> {
+    let width = fitting_width(labels, width);
+    let labels = labels
+        .iter()
+        .map(|l| fit_label(l, width, truncation))
+        .collect::<Vec<_>>();
+
+    // determine how many bars a label spans, including a space to separate it from the next
+    let max_width = labels
+        .iter()
+        .flatten()
+        .map(|l| l.width())
+        .max()
+        .unwrap_or(0);
+    let span = core::cmp::min((max_width + 1).div_ceil(slot_chars), labels.len()).max(1);
+    // the number of lines of a wrapped label
+    let line_count = labels.iter().map(|l| l.len()).max().unwrap_or(1);
+
+    let mut rows: Vec<Vec<(char, Option<usize>)>> = vec![];
+    let mut place = |row_num: usize, col: usize, text: &str, bar: usize| {
+        let columns = text
+            .chars()
+            .flat_map(|c| match c.width() {
+                Some(2) => vec![c, '\0'],
+                _ => vec![c],
+            })
+            .collect::<Vec<_>>();
+        if rows.len() <= row_num {
+            rows.resize(row_num + 1, vec![]);
+        }
+        let row = &mut rows[row_num];
+        if row.len() < col + columns.len() {
+            row.resize(col + columns.len(), (' ', None));
+        }
+        for (j, &c) in columns.iter().enumerate() {
+            row[col + j] = (c, Some(bar));
+        }
+    };
+
+    for (i, lines) in labels.iter().enumerate() {
+        let col = i * slot_chars;
+        match fit {
+            // every label fits on a single line
+            _ if span == 1 => {
+                for (line, text) in lines.iter().enumerate() {
+                    place(line, col, text, i);
+                }
+            }
+            LabelFit::Vertical => {
+                for (row_num, c) in lines.concat().chars().enumerate() {
+                    place(row_num, col, c.encode_utf8(&mut [0; 4]), i);
+                }
+            }
+            LabelFit::Staggered => {
+                for (line, text) in lines.iter().enumerate() {
+                    place((i % span) * line_count + line, col, text, i);
+                }
+            }
+            LabelFit::EveryNth if i % span == 0 => {
+                for (line, text) in lines.iter().enumerate() {
+                    place(line, col, text, i);
+                }
+            }
+            LabelFit::EveryNth => {}
+        }
+    }
+
+    rows
+}
+
+/// `width`, widened if needed so that each character of `labels` fits within it.
+fn fitting_width(labels: &[&str], width: usize) -> usize {
+    let widest_char = labels
+        .iter()
+        .flat_map(|l| l.chars())
+        .filter_map(|c| c.width())
+        .max();
+    width.max(widest_char.unwrap_or(1)).max(1)
+}
+
+/// Fit `label` within `width` columns of the terminal, shortened according to `truncation`.
+/// Returns the lines of the label, of which there is only one unless it is wrapped.
+fn fit_label(label: &str, width: usize, truncation: LabelTruncation) -> Vec<String> {
+    let width = width.max(1);
+    if label.width() <= width {
+        return vec![label.to_string()];
+    }
+    let shortened = match truncation {
+        LabelTruncation::End => format!("{}…", take_width(label.chars(), width - 1)),
+        LabelTruncation::Start => format!("…{}", take_width_end(label, width - 1)),
+        LabelTruncation::Middle => {
+            let start_width = (width - 1).div_ceil(2);
+            format!(
+                "{}…{}",
+                take_width(label.chars(), start_width),
+                take_width_end(label, width - 1 - start_width)
+            )
+        }
+        LabelTruncation::Path => shorten_path(label, width),
+        LabelTruncation::Wrap => return wrap_label(label, width),
+    };
+    vec![shortened]
+}
+
+/// The leading characters of `chars` which fit within `width` columns.
+fn take_width(chars: impl Iterator<Item = char>, width: usize) -> String {
+    let mut taken = 0;
+    chars
+        .take_while(|c| {
+            taken += c.width().unwrap_or(0);
+            taken <= width
+        })
+        .collect()
+}
+
+/// The trailing characters of `text` which fit within `width` columns.
+fn take_width_end(text: &str, width: usize) -> String {
+    let mut end = take_width(text.chars().rev(), width)
+        .chars()
+        .collect::<Vec<_>>();
+    end.reverse();
+    end.into_iter().collect()
+}
+
+/// Abbreviate the directories of `path` to their first character, then omit them,
+/// outermost first, until it fits within `width` columns.
+fn shorten_path(path: &str, width: usize) -> String {
+    let mut parts = path.split('/').map(String::from).collect::<Vec<_>>();
+    let file_name = parts.len() - 1;
+    for i in 0..file_name {
+        if parts.join("/").width() <= width {
+            break;
+        }
+        parts[i] = parts[i].chars().take(1).collect();
+    }
+
+    let mut shortened = parts.join("/");
+    let mut omitted = 0;
+    while shortened.width() > width && omitted < file_name {
+        omitted += 1;
+        shortened = format!("…/{}", parts[omitted..].join("/"));
+    }
+    match shortened.width() <= width {
+        true => shortened,
+        // the file name alone doesn't fit
+        false => format!("…{}", take_width_end(&parts[file_name], width - 1)),
+    }
+}
+
+/// Break `label` into lines of no more than `width` columns, between words if possible.
+fn wrap_label(label: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in label.split(' ') {
+        let separator = if line.is_empty() { 0 } else { 1 };
+        if !line.is_empty() && line.width() + separator + word.width() > width {
+            lines.push(core::mem::take(&mut line));
+        } else if !line.is_empty() {
+            line.push(' ');
+        }
+        // words wider than a line are broken anywhere
+        for c in word.chars() {
+            // as is a character wider than a line, in place of which `…` is written
+            let c = if c.width().unwrap_or(0) > width {
+                '…'
+            } else {
+                c
+            };
+            if !line.is_empty() && line.width() + c.width().unwrap_or(0) > width {
+                lines.push(core::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+impl<'a> core::fmt::Display for Chart<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.canvas())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    // cargo test -- --nocapture
 
     use super::*;
 
+    /// The lines of the chart, without color.
+    fn plain(chart: &Chart) -> Vec<String> {
+        let canvas = chart.canvas();
+        canvas
+            .rows()
+            .map(|row| row.iter().map(|c| c.glyph).collect::<String>())
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
     #[test]
     fn test_two_digit_width() {
         let chart = Chart::new(
@@ -430,6 +1859,7 @@ mod tests {
                 height: 5,
                 view: ViewPreference::Top,
                 display: DisplayMode::Compact,
+                ..ChartOptions::default()
             },
         );
         println!("\nvalue_too_small_for_top\n{chart}");
@@ -444,6 +1874,7 @@ mod tests {
                 height: 5,
                 view: ViewPreference::Bottom,
                 display: DisplayMode::Compact,
+                ..ChartOptions::default()
             },
         );
         println!("\nview_bottom_with_only_large\n{chart}");
@@ -458,6 +1889,7 @@ mod tests {
                 height: 10,
                 view: ViewPreference::Top,
                 display: DisplayMode::Compact,
+                ..ChartOptions::default()
             },
         );
         println!("\nview_top_with_only_small\n{chart}");
@@ -497,8 +1929,783 @@ mod tests {
                         "sixteenth",
                     ],
                 },
+                ..ChartOptions::default()
             },
         );
         println!("\ncomparison_portrait\n{chart}");
     }
+
+    #[test]
+    fn test_x_axis_labels() {
+        let hours = [
+            "00:00", "01:00", "02:00", "03:00", "04:00", "05:00", "06:00", "07:00", "08:00",
+            "09:00", "10:00", "11:00",
+        ];
+        let data = [3, 1, 0, 2, 5, 9, 23, 40, 38, 31, 29, 30];
+        for fit in [LabelFit::Vertical, LabelFit::Staggered, LabelFit::EveryNth] {
+            let name = format!("{fit:?}");
+            let chart = Chart::new(
+                &data,
+                None,
+                ChartOptions {
+                    height: 4,
+                    view: ViewPreference::Bottom,
+                    display: DisplayMode::Portrait { labels: &hours },
+                    x_axis: XAxis::Labels(fit),
+                    ..ChartOptions::default()
+                },
+            );
+            println!("\nx_axis_labels {name}\n{chart}");
+            let axis: &[&str] = match name.as_str() {
+                "Vertical" => &[
+                    "   0 0 0 0 0 0 0 0 0 0 1 1",
+                    "   0 1 2 3 4 5 6 7 8 9 0 1",
+                    "   : : : : : : : : : : : :",
+                    "   0 0 0 0 0 0 0 0 0 0 0 0",
+                    "   0 0 0 0 0 0 0 0 0 0 0 0",
+                ],
+                "Staggered" => &[
+                    "   00:00 03:00 06:00 09:00",
+                    "     01:00 04:00 07:00 10:00",
+                    "       02:00 05:00 08:00 11:00",
+                ],
+                _ => &["   00:00 03:00 06:00 09:00"],
+            };
+            let lines = plain(&chart);
+            assert_eq!(&lines[4..], axis, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_label_axis() {
+        let label_axis = |labels: &[&str], fit: &LabelFit, slot_chars: usize| {
+            super::label_axis(labels, fit, slot_chars, (12, LabelTruncation::End))
+                .into_iter()
+                .map(|row| row.into_iter().map(|(c, _)| c).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        let days = ["Mon", "Tue", "Wed", "Thu"];
+        assert_eq!(
+            label_axis(&days, &LabelFit::Vertical, 4),
+            vec!["Mon Tue Wed Thu"]
+        );
+        assert_eq!(
+            label_axis(&days, &LabelFit::Vertical, 2),
+            vec!["M T W T", "o u e h", "n e d u"]
+        );
+        assert_eq!(
+            label_axis(&days, &LabelFit::Staggered, 2),
+            vec!["Mon Wed", "  Tue Thu"]
+        );
+        assert_eq!(label_axis(&days, &LabelFit::EveryNth, 2), vec!["Mon Wed"]);
+    }
+
+    #[test]
+    fn test_fit_label() {
+        let fit = |label, width, truncation| fit_label(label, width, truncation);
+        let label = "abcdefghijklmnop";
+        assert_eq!(fit(label, 8, LabelTruncation::End), ["abcdefg…"]);
+        assert_eq!(fit(label, 8, LabelTruncation::Start), ["…jklmnop"]);
+        assert_eq!(fit(label, 8, LabelTruncation::Middle), ["abcd…nop"]);
+        assert_eq!(fit("short", 8, LabelTruncation::End), ["short"]);
+
+        // wide characters are measured in columns
+        assert_eq!(fit("東京都渋谷区", 8, LabelTruncation::End), ["東京都…"]);
+        assert_eq!(fit("東京都渋谷区", 8, LabelTruncation::Start), ["…渋谷区"]);
+
+        let path = "src/components/header/title.rs";
+        assert_eq!(
+            fit(path, 20, LabelTruncation::Path),
+            ["s/c/header/title.rs"]
+        );
+        assert_eq!(fit(path, 12, LabelTruncation::Path), ["…/h/title.rs"]);
+        assert_eq!(fit(path, 6, LabelTruncation::Path), ["…le.rs"]);
+
+        assert_eq!(
+            fit("mean response time", 8, LabelTruncation::Wrap),
+            ["mean", "response", "time"]
+        );
+        assert_eq!(
+            fit("abcdefghij k", 4, LabelTruncation::Wrap),
+            ["abcd", "efgh", "ij k"]
+        );
+
+        // no line is wider than `width`, even if a character is
+        assert_eq!(fit("東京", 1, LabelTruncation::Wrap), ["…", "…"]);
+        assert_eq!(fit("東京", 1, LabelTruncation::End), ["…"]);
+        assert_eq!(fit("東京", 0, LabelTruncation::Start), ["…"]);
+    }
+
+    #[test]
+    fn test_wide_labels() {
+        let labels = ["東京", "大阪府大阪市", "札幌"];
+        for (name, x_axis) in [
+            ("table", XAxis::Offsets),
+            ("axis", XAxis::Labels(LabelFit::Staggered)),
+        ] {
+            let chart = Chart::new(
+                &[3, 5, 2],
+                None,
+                ChartOptions {
+                    height: 2,
+                    view: ViewPreference::Bottom,
+                    display: DisplayMode::Portrait { labels: &labels },
+                    x_axis,
+                    label_width: 8,
+                    ..ChartOptions::default()
+                },
+            );
+            let output = chart.to_string();
+            println!("\nwide_labels {name}\n{output}");
+            assert!(!output.contains('\0'));
+            assert!(output.contains("大阪府…"));
+        }
+
+        let chart = Chart::new(
+            &[3, 5],
+            None,
+            ChartOptions {
+                height: 2,
+                display: DisplayMode::Portrait {
+                    labels: &["東京", "wrapped onto lines"],
+                },
+                label_width: 8,
+                label_truncation: LabelTruncation::Wrap,
+                ..ChartOptions::default()
+            },
+        );
+        let output = chart.to_string();
+        println!("\nwide_labels wrapped\n{output}");
+        // padded to the same width, whether wide or not
+        let table = output.lines().skip(3).collect::<Vec<_>>();
+        assert_eq!(
+            table,
+            [
+                " 0: 東京     ",
+                " 1: wrapped  ",
+                "    onto     ",
+                "    lines    "
+            ]
+        );
+
+        // the label width is widened to fit a character, so that labels can be padded
+        for (label_width, label_truncation, first) in [
+            (0, LabelTruncation::End, [" 0: …  ", " 1: ab "]),
+            (1, LabelTruncation::Wrap, [" 0: 東 ", "    京 "]),
+        ] {
+            let chart = Chart::new(
+                &[3, 5],
+                None,
+                ChartOptions {
+                    height: 2,
+                    display: DisplayMode::Portrait {
+                        labels: &["東京", "ab"],
+                    },
+                    label_width,
+                    label_truncation,
+                    ..ChartOptions::default()
+                },
+            );
+            let output = chart.to_string();
+            println!("\nwide_labels width {label_width}\n{output}");
+            assert_eq!(output.lines().skip(3).take(2).collect::<Vec<_>>(), first);
+        }
+
+        // a caption is centered below the bars by its width
+        let chart = Chart::new(
+            &[3, 5, 1, 1, 1, 1],
+            None,
+            ChartOptions {
+                height: 2,
+                x_caption: Some("大阪"),
+                ..ChartOptions::default()
+            },
+        );
+        let output = chart.to_string();
+        println!("\nwide_labels caption\n{output}");
+        assert!(output.contains("\n   大阪\n"));
+    }
+
+    #[test]
+    fn test_outliers() {
+        let chart = Chart::new(
+            &[1, 2, 500, 3],
+            Some(ChartComparison {
+                data: &[1, 900, 2, 0],
+            }),
+            ChartOptions {
+                height: 2,
+                view: ViewPreference::Bottom,
+                sort: Some(Sort {
+                    key: SortKey::Primary,
+                    order: SortOrder::Descending,
+                }),
+                ..ChartOptions::default()
+            },
+        );
+        // offsets as displayed, after sorting
+        assert_eq!(chart.data(), [500, 3, 2, 1]);
+        assert_eq!(chart.compare_data(), Some(&[2, 0, 900, 1][..]));
+        assert_eq!(
+            chart.outliers(),
+            [(0, Series::Primary), (2, Series::Comparison)]
+        );
+    }
+
+    #[test]
+    fn test_sort_and_limit() {
+        let labels = ["a", "b", "c", "d", "e", "f"];
+        let chart = Chart::new(
+            &[5, 40, 12, 0, 40, 7],
+            Some(ChartComparison {
+                data: &[6, 30, 20, 1, 2, 7],
+            }),
+            ChartOptions {
+                display: DisplayMode::Portrait { labels: &labels },
+                sort: Some(Sort {
+                    key: SortKey::Difference,
+                    order: SortOrder::Descending,
+                }),
+                limit: Some(Limit {
+                    end: LimitEnd::Top,
+                    count: 4,
+                    others: true,
+                }),
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(&chart.data[..], &[12, 5, 0, 7, 80]);
+        assert_eq!(chart.compare.as_deref(), Some(&[20, 6, 1, 7, 32][..]));
+        assert_eq!(
+            chart.labels.as_deref(),
+            Some(&["c", "a", "d", "f", "others"][..])
+        );
+        println!("\nsort_and_limit\n{chart}");
+
+        let chart = Chart::new(
+            &[5, 40, 12, 0, 40, 7],
+            None,
+            ChartOptions {
+                display: DisplayMode::Portrait { labels: &labels },
+                sort: Some(Sort {
+                    key: SortKey::Label,
+                    order: SortOrder::Descending,
+                }),
+                limit: Some(Limit {
+                    end: LimitEnd::Bottom,
+                    count: 2,
+                    others: false,
+                }),
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(&chart.data[..], &[40, 5]);
+        assert_eq!(chart.labels.as_deref(), Some(&["b", "a"][..]));
+    }
+
+    #[test]
+    fn test_limit_more_than_100() {
+        let data = (0..1000).collect::<Vec<u32>>();
+        let chart = Chart::new(
+            &data,
+            None,
+            ChartOptions {
+                limit: Some(Limit {
+                    end: LimitEnd::Top,
+                    count: 20,
+                    others: false,
+                }),
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(&chart.data[..], (980..1000).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "limit count should be at least 1")]
+    fn test_limit_zero() {
+        Chart::new(
+            &[3, 1],
+            None,
+            ChartOptions {
+                display: DisplayMode::Portrait {
+                    labels: &["a", "b"],
+                },
+                limit: Some(Limit {
+                    end: LimitEnd::Top,
+                    count: 0,
+                    others: true,
+                }),
+                ..ChartOptions::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let labels = ["the", "of", "and", "chart"];
+        let chart = Chart::new(
+            &[9000, 6000, 2988, 12],
+            Some(ChartComparison {
+                data: &[300, 100, 120, 80],
+            }),
+            ChartOptions {
+                height: 6,
+                view: ViewPreference::Bottom,
+                display: DisplayMode::Portrait { labels: &labels },
+                normalize: Some(Normalize::Total),
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(
+            chart.measurements(),
+            (
+                &[500_000, 333_333, 166_000, 667][..],
+                Some(&[500_000, 166_667, 200_000, 133_333][..])
+            )
+        );
+        println!("\nnormalize\n{chart}");
+    }
+
+    #[test]
+    fn test_stacked() {
+        let chart = Chart::stacked(
+            &[
+                &[12, 30, 4, 0, 21, 9],
+                &[3, 18, 0, 0, 7, 20],
+                &[7, 2, 11, 0, 14, 1],
+            ],
+            ChartOptions {
+                height: 4,
+                view: ViewPreference::Bottom,
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(&chart.data[..], &[22, 50, 15, 0, 42, 30]);
+        println!("\nstacked\n{chart}");
+
+        let cell = |layers: &[&[u32]]| {
+            let chart = Chart::stacked(
+                layers,
+                ChartOptions {
+                    height: 1,
+                    y_max: Some(8),
+                    ..ChartOptions::default()
+                },
+            );
+            let canvas = chart.canvas();
+            let row = canvas.row(0).unwrap();
+            *row.iter()
+                .find(|c| matches!(c.role, Role::Bar { .. }))
+                .unwrap()
+        };
+        // the stack ends within the cell, so the top series is drawn to its height
+        let top = cell(&[&[3], &[2]]);
+        assert_eq!((top.glyph, top.style), ('▅', Style::fg(STACK_COLORS[1])));
+        // the second series fills the rest of the cell, behind the first
+        let boundary = cell(&[&[3], &[5]]);
+        assert_eq!(boundary.glyph, '▃');
+        assert_eq!(
+            boundary.style,
+            Style::fg(STACK_COLORS[0]).on(STACK_COLORS[1])
+        );
+        // a series which ends within the cell is hidden by a later one
+        let hidden = cell(&[&[3], &[1], &[4]]);
+        assert_eq!(hidden.glyph, '▃');
+        assert_eq!(hidden.style, Style::fg(STACK_COLORS[0]).on(STACK_COLORS[2]));
+        let empty_top = cell(&[&[3], &[2], &[0]]);
+        assert_eq!(empty_top.style, Style::fg(STACK_COLORS[1]));
+    }
+
+    #[test]
+    fn test_summary() {
+        let chart = Chart::new(
+            &[
+                0, 22, 2, 9, 223, 34, 33, 66, 76, 122, 199, 33, 12, 89, 1222, 100,
+            ],
+            Some(ChartComparison {
+                data: &[
+                    14, 20, 1, 8, 223, 12, 56, 79, 69, 100, 1122, 33, 45, 9, 9000, 78,
+                ],
+            }),
+            ChartOptions {
+                height: 6,
+                view: ViewPreference::Bottom,
+                summary: &Statistic::ALL,
+                ..ChartOptions::default()
+            },
+        );
+        println!("\nsummary\n{chart}");
+        let lines = plain(&chart);
+        assert_eq!(lines[7], "          primary  compare");
+        for row in [
+            "count          16       16",
+            "sum          2242    10869",
+            "max          1222     9000",
+            "median         50     50.5",
+            "zeros           1        0",
+            "outliers        8        8",
+            "delta      +8627 (+384.8%)",
+        ] {
+            assert!(lines.iter().any(|line| line == row), "{row}");
+        }
+    }
+
+    #[test]
+    fn test_reference_lines() {
+        let chart = Chart::new(
+            &[12, 30, 4, 0, 21, 9, 17],
+            Some(ChartComparison {
+                data: &[10, 34, 6, 3, 18, 12, 15],
+            }),
+            ChartOptions {
+                height: 6,
+                view: ViewPreference::Bottom,
+                reference_lines: &[
+                    ReferenceLine::Threshold(25),
+                    ReferenceLine::Mean,
+                    ReferenceLine::Target(Series::Comparison, 5),
+                    ReferenceLine::Threshold(200),
+                ],
+                ..ChartOptions::default()
+            },
+        );
+        println!("\nreference_lines\n{chart}");
+        let lines = plain(&chart);
+        // the threshold of 200 is above the range, indicated next to the top tick
+        assert!(lines[0].starts_with("34🢁│"));
+        assert!(lines[1].starts_with("25─│───██───"));
+        // the mean of the primary series is 13.3
+        assert!(lines[3].starts_with("13┄│┄┄┄██┄┄┄"));
+        // the comparison target is drawn through comparison bars only, and labeled with
+        // its own value in place of the bottom tick
+        assert!(lines[5].starts_with(" 5·│"));
+
+        let chart = Chart::new(
+            &[12, 30, 14, 21],
+            None,
+            ChartOptions {
+                height: 4,
+                view: ViewPreference::Bottom,
+                y_min: Some(10),
+                reference_lines: &[ReferenceLine::Threshold(2)],
+                ..ChartOptions::default()
+            },
+        );
+        println!("\nreference_lines below range\n{chart}");
+        let lines = plain(&chart);
+        assert!(lines[3].contains("🢃│"));
+        assert!(!lines.iter().any(|line| line.contains('─')));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let labels = ["mon", "tue", "wed", "thu", "fri"];
+        let data = [23, 31, 12, 40, 8];
+        let chart = Chart::new(
+            &data,
+            None,
+            ChartOptions {
+                height: 4,
+                view: ViewPreference::Bottom,
+                display: DisplayMode::Portrait { labels: &labels },
+                sort: Some(Sort {
+                    key: SortKey::Primary,
+                    order: SortOrder::Descending,
+                }),
+                highlight: Highlight::Indices(&[2, 4]),
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(chart.highlighted, vec![false, false, false, true, true]);
+        println!("\nhighlight\n{chart}");
+
+        // wed and fri are sorted to the end, drawn in bright cyan, with their offsets in
+        // reverse video below the bars, and marked by `*` in the label table
+        let canvas = chart.canvas();
+        let cells = canvas.rows().flatten().collect::<Vec<_>>();
+        for offset in 0..5 {
+            let highlighted = offset >= 3;
+            // thu is too large to be displayed, drawn with outlier markers instead
+            let is_bar = |role| match role {
+                Role::Bar { offset: o, .. } | Role::OutlierMarker { offset: o, .. } => o == offset,
+                _ => false,
+            };
+            let bar = cells
+                .iter()
+                .filter(|cell| is_bar(cell.role))
+                .collect::<Vec<_>>();
+            assert!(!bar.is_empty());
+            assert!(
+                bar.iter()
+                    .all(|cell| (cell.style.fg == Some(Color::BrightCyan)) == highlighted)
+            );
+            let below_bar = cells.iter().find(|cell| cell.role == Role::Offset(offset));
+            assert_eq!(below_bar.unwrap().style.reversed, highlighted);
+            let label = cells.iter().find(|cell| cell.role == Role::Label(offset));
+            assert_eq!(
+                label.unwrap().style.fg == Some(Color::BrightCyan),
+                highlighted
+            );
+        }
+        let lines = plain(&chart);
+        assert_eq!(
+            lines[5..],
+            [" 0: thu", " 1: tue", " 2: mon", " 3* wed", " 4* fri"]
+        );
+
+        let starts_with_t = |_: u32, label: Option<&str>| label.is_some_and(|l| l.starts_with('t'));
+        let chart = Chart::new(
+            &data,
+            None,
+            ChartOptions {
+                height: 4,
+                display: DisplayMode::Portrait { labels: &labels },
+                highlight: Highlight::Predicate(&starts_with_t),
+                ..ChartOptions::default()
+            },
+        );
+        assert_eq!(chart.highlighted, vec![false, true, false, true, false]);
+    }
+
+    #[test]
+    #[should_panic(expected = "highlight indices should be less than data length")]
+    fn test_highlight_out_of_range() {
+        Chart::new(
+            &[3, 1],
+            None,
+            ChartOptions {
+                highlight: Highlight::Indices(&[2]),
+                ..ChartOptions::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_captions_and_legend() {
+        let chart = Chart::new(
+            &[0, 12, 30, 7, 200, 18],
+            Some(ChartComparison {
+                data: &[2, 9, 31, 7, 150, 11],
+            }),
+            ChartOptions {
+                height: 4,
+                view: ViewPreference::Bottom,
+                reference_lines: &[ReferenceLine::Threshold(20)],
+                title: Some("word counts"),
+                y_caption: Some("words"),
+                x_caption: Some("chapter"),
+                legend: true,
+                ..ChartOptions::default()
+            },
+        );
+        let output = chart.to_string();
+        println!("\ncaptions and legend\n{output}");
+        let lines = output.lines().collect::<Vec<_>>();
+        assert!(lines[0].contains("word counts"));
+        assert_eq!(lines[1], "words");
+        assert_eq!(lines[7], "         chapter");
+        for meaning in ["improved", "regressed", "threshold", "above range", "zero"] {
+            assert!(lines[8].contains(meaning), "{meaning}");
+        }
+        assert!(!lines[8].contains("below range"));
+    }
+
+    #[test]
+    fn test_joined() {
+        let before = [("fn", 40), ("let", 31), ("match", 12), ("loop", 0)];
+        let after = [("let", 28), ("fn", 44), ("impl", 9), ("loop", 3)];
+        let chart = Chart::joined(
+            &before,
+            &after,
+            Join::Outer,
+            ChartOptions {
+                height: 4,
+                view: ViewPreference::Bottom,
+                legend: true,
+                ..ChartOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            chart.labels.as_deref(),
+            Some(&["fn", "let", "match", "loop", "impl"][..])
+        );
+        assert_eq!(chart.data.as_ref(), &[40, 31, 12, 0, 0]);
+        assert_eq!(chart.compare.as_deref(), Some(&[44, 28, 0, 3, 9][..]));
+        assert_eq!(chart.missing[2], (false, true));
+        assert_eq!(chart.missing[4], (true, false));
+        let output = chart.to_string();
+        println!("\njoined outer\n{output}");
+        assert_eq!(output.matches('∅').count(), 3);
+
+        let chart = Chart::joined(
+            &before,
+            &after,
+            Join::Inner,
+            ChartOptions {
+                height: 4,
+                display: DisplayMode::Portrait {
+                    labels: &["ignored"],
+                },
+                sort: Some(Sort {
+                    key: SortKey::Label,
+                    order: SortOrder::Ascending,
+                }),
+                ..ChartOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(chart.labels.as_deref(), Some(&["fn", "let", "loop"][..]));
+        assert_eq!(chart.compare.as_deref(), Some(&[44, 28, 3][..]));
+        assert!(chart.missing.iter().all(|&m| m == (false, false)));
+
+        // no label in common
+        let disjoint = [("impl", 9)];
+        assert!(Chart::joined(&before, &disjoint, Join::Inner, ChartOptions::default()).is_none());
+        assert!(Chart::joined(&before, &disjoint, Join::Outer, ChartOptions::default()).is_some());
+        assert_eq!(
+            Join::Outer.pair(&[("a", 1), ("a", 2)], &[("a", 3)]),
+            [("a", Some(1), Some(3)), ("a", Some(2), None)]
+        );
+    }
+
+    #[test]
+    fn test_y_range() {
+        let data = [3, 8, 12, 30, 22, 9];
+        let chart = |y_min, y_max| {
+            Chart::new(
+                &data,
+                None,
+                ChartOptions {
+                    height: 4,
+                    y_min,
+                    y_max,
+                    ..ChartOptions::default()
+                },
+            )
+        };
+
+        let fixed = chart(None, Some(40));
+        let (steps, _) = fixed.scale_to_steps(&fixed.scale());
+        assert_eq!(steps, vec![2, 6, 9, 24, 17, 7]);
+        println!("\ny_max\n{fixed}");
+
+        let zoomed = chart(Some(10), Some(25));
+        let (steps, _) = zoomed.scale_to_steps(&zoomed.scale());
+        assert_eq!(steps, vec![-1, -1, 4, -2, 25, -1]);
+        println!("\ny_min y_max\n{zoomed}");
+
+        let baseline = chart(Some(10), None);
+        assert_eq!(baseline.scale().max_measurement, 30);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let steps = |data: &[u32], rounding| {
+            let chart = Chart::new(
+                data,
+                None,
+                ChartOptions {
+                    height: 2,
+                    view: ViewPreference::Bottom,
+                    rounding,
+                    ..ChartOptions::default()
+                },
+            );
+            chart.scale_to_steps(&chart.scale()).0
+        };
+        // 16 steps for 100, so 6.25 steps per 39 and 9.76 per 61
+        let data = [39, 61, 100];
+        assert_eq!(steps(&data, Rounding::Floor), vec![6, 9, 16]);
+        assert_eq!(steps(&data, Rounding::Nearest), vec![6, 10, 16]);
+        assert_eq!(steps(&data, Rounding::Ceil), vec![7, 10, 16]);
+
+        // values above 2^24, which are not distinguished by f32 arithmetic
+        let large = [33_554_391, 33_554_392];
+        assert_eq!(steps(&large, Rounding::Floor), vec![15, 16]);
+    }
+
+    #[test]
+    fn test_write_to() {
+        let chart = Chart::new(
+            &[3, 0, 12, 7],
+            Some(ChartComparison {
+                data: &[4, 1, 9, 7],
+            }),
+            ChartOptions {
+                height: 3,
+                view: ViewPreference::Bottom,
+                summary: &[Statistic::Count, Statistic::Max],
+                ..ChartOptions::default()
+            },
+        );
+        let expected = chart.to_string();
+
+        let mut canvas = Canvas::default();
+        let mut bytes = vec![];
+        chart.write_to(&mut canvas, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+
+        // a smaller chart drawn on the same canvas replaces the larger one
+        let small = Chart::new(&[1, 2], None, ChartOptions::default());
+        let mut bytes = vec![];
+        small.write_to(&mut canvas, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), small.to_string());
+        assert_eq!(canvas, small.canvas());
+
+        // errors of the writer are returned
+        struct Full;
+        impl std::io::Write for Full {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::StorageFull.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let error = chart.write_to(&mut canvas, &mut Full).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+
+        // appended to existing text
+        let mut text = String::from("before\n");
+        chart.render_into(&mut canvas, &mut text);
+        assert_eq!(text, format!("before\n{expected}"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_steps_monotonic(
+            data in proptest::collection::vec(0u32..100_000, 1..=100),
+            height in 1u16..=16,
+            top in proptest::bool::ANY,
+            rounding in proptest::sample::select(vec![
+                Rounding::Floor,
+                Rounding::Nearest,
+                Rounding::Ceil,
+            ]),
+        ) {
+            let chart = Chart::new(
+                &data,
+                None,
+                ChartOptions {
+                    height,
+                    view: if top { ViewPreference::Top } else { ViewPreference::Bottom },
+                    rounding,
+                    ..ChartOptions::default()
+                },
+            );
+            let (steps, _) = chart.scale_to_steps(&chart.scale());
+            for i in 0..data.len() {
+                for j in 0..data.len() {
+                    // equal values render the same, and larger visible values are not shorter
+                    if data[i] == data[j] {
+                        proptest::prop_assert_eq!(steps[i], steps[j]);
+                    } else if data[i] < data[j] && steps[i] > 0 && steps[j] > 0 {
+                        proptest::prop_assert!(steps[i] <= steps[j]);
+                    }
+                }
+            }
+        }
+    }
 }
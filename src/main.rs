@@ -1,49 +1,279 @@
 use auto_args::AutoArgs;
+use std::io::{BufRead, IsTerminal, Read};
 
-use chart_relative::{Chart, params::*};
+use chart_relative::{
+    Chart,
+    boxplot::{BoxPlot, BoxPlotOptions},
+    canvas::Canvas,
+    image::PngOptions,
+    params::*,
+};
 
-#[derive(Debug, AutoArgs)]
-enum ViewOpt {
-    Bottom,
-    Top,
+mod baseline;
+mod config;
+mod json;
+
+/// Declare an enum of the choices of an option, such as `--view-top`.
+macro_rules! choices {
+    (enum $name:ident { $($variant:ident,)* }) => {
+        #[derive(Debug, AutoArgs)]
+        enum $name {
+            $($variant,)*
+        }
+
+        impl config::Choices for $name {
+            const VARIANTS: &[&str] = &[$(stringify!($variant),)*];
+        }
+    };
+}
+
+/// Declare the struct of options, and `options` listing them for `config::resolve`.
+macro_rules! options {
+    (
+        $(#[$meta:meta])*
+        struct $name:ident {
+            $($(#[$field_meta:meta])* $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$meta])*
+        struct $name {
+            $($(#[$field_meta])* $field: $ty,)*
+        }
+
+        impl $name {
+            fn options() -> config::Options {
+                config::Options::new(vec![
+                    $((stringify!($field), <$ty as config::OptionKind>::kind()),)*
+                ])
+            }
+        }
+    };
+}
+
+choices! {
+    enum ViewOpt {
+        Bottom,
+        Top,
+    }
+}
+
+choices! {
+    enum XLabelsOpt {
+        Vertical,
+        Staggered,
+        EveryNth,
+    }
 }
 
-#[derive(Debug, AutoArgs)]
-struct Opt {
-    /// Maximum number of lines to use for display before scaling. default: 16
-    max_height: Option<u16>,
-    /// Which end of range to show, if too large to show all. default: bottom
-    view: Option<ViewOpt>,
+choices! {
+    enum LabelTruncationOpt {
+        End,
+        Middle,
+        Start,
+        Path,
+        Wrap,
+    }
+}
+
+choices! {
+    enum RoundingOpt {
+        Floor,
+        Nearest,
+        Ceil,
+    }
+}
+
+choices! {
+    enum NormalizeOpt {
+        Total,
+        Max,
+    }
+}
+
+/// The values of --format.
+#[derive(Debug, Clone, Copy)]
+enum OutputOpt {
+    Text,
+    Png,
+    Markdown,
+}
+
+choices! {
+    enum InputFormatOpt {
+        Columns,
+        Json,
+        Ndjson,
+    }
+}
+
+choices! {
+    enum JoinOpt {
+        Label,
+        Line,
+    }
+}
+
+choices! {
+    enum FillOpt {
+        Zero,
+        Drop,
+        Error,
+    }
+}
+
+options! {
+    #[derive(Debug, AutoArgs)]
+    struct Opt {
+        /// Maximum number of lines to use for display before scaling. default: 16
+        max_height: Option<u16>,
+        /// Which end of range to show, if too large to show all. default: bottom
+        view: Option<ViewOpt>,
+        /// Write labels below the bars instead of in a table, arranged this way if too wide.
+        x_labels: Option<XLabelsOpt>,
+        /// Maximum width of a label, in columns of the terminal. default: 12
+        label_width: Option<usize>,
+        /// How to shorten wider labels: replace the end, middle or start with an ellipsis, shorten the directories of a path, or wrap onto several lines. default: end
+        label_truncation: Option<LabelTruncationOpt>,
+        /// Order of the bars: primary, compare, difference or label, optionally followed by :asc or :desc. default: input order
+        sort: Option<String>,
+        /// Only show this many bars, at least 1, with the largest values of the sort key. Input is not truncated.
+        top: Option<usize>,
+        /// Add a bar labeled "others" holding the sum of the values not shown by --top.
+        others: bool,
+        /// The value at the top of the chart. Larger values are indicated by 🢁. default: largest value
+        y_max: Option<u32>,
+        /// The value at the bottom of the chart. Smaller values are indicated by 🢃. default: 0
+        y_min: Option<u32>,
+        /// How the height of each bar is rounded to 1/8 of a line. default: floor
+        rounding: Option<RoundingOpt>,
+        /// Show each column as a percentage of its total or of its largest value.
+        normalize: Option<NormalizeOpt>,
+        /// Statistics to show below the chart, separated by commas: count, sum, min, max, mean, median, stddev, zeros, outliers, delta. Or all.
+        summary: Option<String>,
+        /// Draw a horizontal line at this value.
+        threshold: Option<u32>,
+        /// Draw a horizontal line at the mean of the first column.
+        mean_line: bool,
+        /// Draw a horizontal line at the median of the first column.
+        median_line: bool,
+        /// Draw a horizontal line at this value, through the bars of the first column.
+        target: Option<u32>,
+        /// Draw a horizontal line at this value, through the bars of the comparison column.
+        compare_target: Option<u32>,
+        /// Bars to draw attention to: offsets separated by commas, or a regular expression matching labels.
+        highlight: Option<String>,
+        /// Write this above the chart.
+        title: Option<String>,
+        /// The unit of the values, written above the chart.
+        y_caption: Option<String>,
+        /// What the bars represent, written below the chart.
+        x_caption: Option<String>,
+        /// Explain the colors and markers used in the chart.
+        legend: bool,
+        /// Draw a box plot of the values of each label, instead of a bar for each line.
+        box_plot: bool,
+        /// How the chart is written: text, png for an image to be redirected to a file, or markdown for a code block and a table of values. default: text
+        format: Option<String>,
+        /// After the chart, exit with code 3 if a value shown is larger than this.
+        fail_if_above: Option<u32>,
+        /// After the chart, exit with code 4 if a comparison bar shown is larger than its primary bar by more than this percentage, of at least 0. Bars are compared as drawn, after --normalize.
+        fail_if_regressed: Option<f64>,
+        /// After the chart, exit with code 5 if a value shown is outside of the displayed range.
+        fail_if_outliers: bool,
+        /// How input is structured, as described below. default: columns
+        input_format: Option<InputFormatOpt>,
+        /// Field of JSON records holding the label. Nested fields are separated by dots. default: label
+        label_field: Option<String>,
+        /// Field of JSON records holding the value. default: value
+        value_field: Option<String>,
+        /// Field of JSON records holding the comparison value. default: compare
+        compare_field: Option<String>,
+        /// How to match the values of two input files. default: label, if both are labeled
+        join: Option<JoinOpt>,
+        /// What to do with values which only one of two input files has. default: zero
+        fill: Option<FillOpt>,
+        /// Store the values and labels of the first column as a baseline with this name, described below.
+        save_baseline: Option<String>,
+        /// Compare the input with the baseline of this name, saved by an earlier run.
+        baseline: Option<String>,
+        /// Use the options of this profile of the config file, described below.
+        // applied by `config::resolve`, before the options are parsed
+        #[allow(dead_code)]
+        profile: Option<String>,
+        /// Show the value of each option which is set, and where it was found.
+        print_config: bool,
+    }
 }
 
 fn main() {
     help_intercept();
-    let args = Opt::from_args();
-    let cols = StdinColumns::new();
-
-    let data_primary = match cols.try_integers(0) {
-        Ok(data) => data,
-        Err(_) => {
-            eprintln!("Invalid line. First column should be integers.");
+    let (args, files) = parse_args();
+    // with --top, all of the input is ranked before the chart is truncated
+    // with --box-plot, each label may have any number of values
+    let truncate = args.top.is_none() && !args.box_plot;
+    let output = match args.format.as_deref() {
+        None | Some("text") => OutputOpt::Text,
+        Some("png") => OutputOpt::Png,
+        Some("markdown") => OutputOpt::Markdown,
+        Some(format) => {
+            eprintln!("Invalid --format '{format}': expected text, png or markdown.");
             eprintln!("For more information try --help");
             std::process::exit(1);
         }
     };
+    if args.top == Some(0) {
+        eprintln!("Invalid --top 0: expected at least 1.");
+        eprintln!("For more information try --help");
+        std::process::exit(1);
+    }
 
-    let mut data_compare: Vec<u32> = vec![];
-    #[allow(unused_assignments)]
-    let mut labels: Vec<&str> = vec![];
+    let (data_primary, data_compare, labels) = match files.as_slice() {
+        _ if args.baseline.is_some() => read_with_baseline(&files, &args, truncate),
+        [] => read_input("-", &args, truncate),
+        [path] => read_input(path, &args, truncate),
+        [primary, compare] => {
+            let primary = read_one_series(primary, &args, truncate);
+            let compare = read_one_series(compare, &args, truncate);
+            join_series(primary, compare, &args.join, &args.fill)
+        }
+        _ => {
+            eprintln!("Too many input files: expected no more than 2.");
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+    };
+    if data_primary.is_empty() && data_compare.is_empty() {
+        eprintln!("No data: the input has no values.");
+        std::process::exit(1);
+    }
+    // with --baseline, the input is saved by `read_with_baseline`, before it's joined
+    if args.baseline.is_none() {
+        save_baseline(&data_primary, &labels, &args);
+    }
+    let labels = labels.iter().map(|l| l.as_str()).collect::<Vec<_>>();
 
-    if let Ok(ints) = cols.try_integers(1) {
-        data_compare = ints;
-        labels = cols.strings(2);
-    } else {
-        labels = cols.strings(1);
-        if !cols.strings(2).is_empty() {
-            eprintln!("Invalid line. found: integer string string");
+    if args.box_plot {
+        if labels.is_empty() || !data_compare.is_empty() {
+            eprintln!("--box-plot requires labeled data, with a line for each value.");
             eprintln!("For more information try --help");
             std::process::exit(1);
         }
+        print_box_plot(&data_primary, &labels, &args, output);
+        return;
+    }
+
+    if args.fail_if_regressed.is_some() && data_compare.is_empty() {
+        eprintln!("--fail-if-regressed requires comparison data.");
+        eprintln!("For more information try --help");
+        std::process::exit(1);
+    }
+    if args
+        .fail_if_regressed
+        .is_some_and(|percent| percent < 0.0 || percent.is_nan())
+    {
+        eprintln!("Invalid --fail-if-regressed: expected a percentage of at least 0.");
+        eprintln!("For more information try --help");
+        std::process::exit(1);
     }
 
     let comparison = if !data_compare.is_empty() {
@@ -65,31 +295,625 @@ fn main() {
         _ => ViewPreference::Bottom,
     };
 
+    let x_axis = match args.x_labels {
+        _ if labels.is_empty() => XAxis::Offsets,
+        None => XAxis::Offsets,
+        Some(XLabelsOpt::Vertical) => XAxis::Labels(LabelFit::Vertical),
+        Some(XLabelsOpt::Staggered) => XAxis::Labels(LabelFit::Staggered),
+        Some(XLabelsOpt::EveryNth) => XAxis::Labels(LabelFit::EveryNth),
+    };
+
+    let sort = args.sort.as_deref().map(|s| match parse_sort(s) {
+        Some(sort) => {
+            let missing = match sort.key {
+                SortKey::Comparison | SortKey::Difference if data_compare.is_empty() => {
+                    Some("comparison data")
+                }
+                SortKey::Label if labels.is_empty() => Some("labels"),
+                _ => None,
+            };
+            if let Some(missing) = missing {
+                eprintln!("Invalid --sort '{s}': input has no {missing}.");
+                std::process::exit(1);
+            }
+            sort
+        }
+        None => {
+            eprintln!("Invalid --sort '{s}'.");
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+    });
+
+    let summary = match args.summary.as_deref().map(parse_summary) {
+        Some(Some(summary)) => summary,
+        Some(None) => {
+            eprintln!("Invalid --summary '{}'.", args.summary.unwrap());
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+        None => vec![],
+    };
+
+    let mut reference_lines = vec![];
+    if let Some(value) = args.threshold {
+        reference_lines.push(ReferenceLine::Threshold(value));
+    }
+    if args.mean_line {
+        reference_lines.push(ReferenceLine::Mean);
+    }
+    if args.median_line {
+        reference_lines.push(ReferenceLine::Median);
+    }
+    if let Some(value) = args.target {
+        reference_lines.push(ReferenceLine::Target(Series::Primary, value));
+    }
+    if let Some(value) = args.compare_target {
+        reference_lines.push(ReferenceLine::Target(Series::Comparison, value));
+    }
+
+    let highlight_indices: Option<Vec<usize>> = args
+        .highlight
+        .as_deref()
+        .and_then(|h| h.split(',').map(|i| i.trim().parse().ok()).collect());
+    if let Some(&i) = highlight_indices
+        .iter()
+        .flatten()
+        .find(|&&i| i >= data_primary.len())
+    {
+        eprintln!(
+            "Invalid --highlight {i}: expected offsets below {}.",
+            data_primary.len()
+        );
+        eprintln!("For more information try --help");
+        std::process::exit(1);
+    }
+    let highlight_pattern = match (&args.highlight, &highlight_indices) {
+        (Some(pattern), None) => match regex::Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                eprintln!("Invalid --highlight '{pattern}': {e}");
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+    let highlight_match = |_: u32, label: Option<&str>| -> bool {
+        let re = highlight_pattern.as_ref().unwrap();
+        label.is_some_and(|l| re.is_match(l))
+    };
+    let highlight = match (&highlight_indices, &highlight_pattern) {
+        (Some(indices), _) => Highlight::Indices(indices),
+        (None, Some(_)) => Highlight::Predicate(&highlight_match),
+        (None, None) => Highlight::None,
+    };
+
+    let truncation = label_truncation(&args);
+
+    let limit = args.top.map(|count| Limit {
+        end: LimitEnd::Top,
+        count,
+        others: args.others,
+    });
+
     let mut max = data_primary.iter().max().unwrap();
     if let Some(ChartComparison { data }) = comparison {
         max = std::cmp::max(max, data.iter().max().unwrap());
     }
+    let range = args
+        .y_max
+        .unwrap_or(*max)
+        .saturating_sub(args.y_min.unwrap_or(0))
+        .max(1);
     let chart = Chart::new(
         &data_primary,
         comparison,
         ChartOptions {
-            height: std::cmp::min(*max as u16, args.max_height.unwrap_or(16)),
+            height: std::cmp::min(range, args.max_height.unwrap_or(16) as u32) as u16,
             view,
             display,
+            x_axis,
+            sort,
+            limit,
+            y_max: args.y_max,
+            y_min: args.y_min,
+            rounding: match args.rounding {
+                None | Some(RoundingOpt::Floor) => Rounding::Floor,
+                Some(RoundingOpt::Nearest) => Rounding::Nearest,
+                Some(RoundingOpt::Ceil) => Rounding::Ceil,
+            },
+            normalize: args.normalize.as_ref().map(|n| match n {
+                NormalizeOpt::Total => Normalize::Total,
+                NormalizeOpt::Max => Normalize::Max,
+            }),
+            summary: &summary,
+            reference_lines: &reference_lines,
+            highlight,
+            title: args.title.as_deref(),
+            y_caption: args.y_caption.as_deref(),
+            x_caption: args.x_caption.as_deref(),
+            legend: args.legend,
+            label_width: args.label_width.unwrap_or(12),
+            label_truncation: truncation,
         },
     );
 
-    print!("{chart}");
+    match output {
+        OutputOpt::Text => print!("{chart}"),
+        OutputOpt::Png => write_png(&chart.canvas()),
+        OutputOpt::Markdown => print!("{}", chart.markdown()),
+    }
+
+    // the bars which fail the checks of the --fail-if options are described after the chart
+    let failures = check(&chart, &args);
+    for failure in &failures {
+        eprintln!("failed: {}", failure.condition);
+        for bar in &failure.bars {
+            eprintln!("  {bar}");
+        }
+    }
+    if let Some(failure) = failures.first() {
+        std::process::exit(failure.exit_code);
+    }
+}
+
+/// A check of the --fail-if options, and the bars which fail it.
+struct Failure {
+    exit_code: i32,
+    condition: String,
+    bars: Vec<String>,
+}
+
+/// Check the bars shown by `chart` according to the --fail-if options.
+fn check(chart: &Chart, args: &Opt) -> Vec<Failure> {
+    let (data, compare, labels) = (chart.data(), chart.compare_data(), chart.labels());
+    let bar = |i: usize| match labels {
+        Some(labels) => format!("{i} {}", labels[i]),
+        None => i.to_string(),
+    };
+    let value = |i: usize, series: Series| match (series, compare) {
+        (Series::Comparison, Some(compare)) => compare[i],
+        _ => data[i],
+    };
+    let series_name = |series: Series| match series {
+        Series::Primary => "primary",
+        Series::Comparison => "compare",
+    };
+    let all_values = (0..data.len())
+        .flat_map(|i| [(i, Series::Primary), (i, Series::Comparison)])
+        .filter(|&(_, series)| series == Series::Primary || compare.is_some());
+
+    let mut failures = vec![];
+    if let Some(limit) = args.fail_if_above {
+        failures.push(Failure {
+            exit_code: 3,
+            condition: format!("values above {limit}"),
+            bars: all_values
+                .clone()
+                .filter(|&(i, series)| value(i, series) > limit)
+                .map(|(i, series)| {
+                    format!("{}: {} {}", bar(i), series_name(series), value(i, series))
+                })
+                .collect(),
+        });
+    }
+    // bars are compared as drawn, as for the colors of regressed bars
+    let measurements = chart.measurements();
+    if let (Some(percent), (primary, Some(measured))) = (args.fail_if_regressed, measurements) {
+        failures.push(Failure {
+            exit_code: 4,
+            condition: format!("comparison values more than {percent}% above primary values"),
+            bars: (0..data.len())
+                .filter(|&i| measured[i] as f64 > primary[i] as f64 * (1.0 + percent / 100.0))
+                .map(|i| {
+                    let change = match primary[i] {
+                        0 => String::new(),
+                        p => {
+                            let change = (measured[i] as i64 - p as i64) as f64 * 100.0 / p as f64;
+                            let drawn = if args.normalize.is_some() {
+                                " as drawn"
+                            } else {
+                                ""
+                            };
+                            format!(" ({change:+.1}%{drawn})")
+                        }
+                    };
+                    let compare = compare.map_or(0, |c| c[i]);
+                    format!("{}: {} -> {compare}{change}", bar(i), data[i])
+                })
+                .collect(),
+        });
+    }
+    if args.fail_if_outliers {
+        failures.push(Failure {
+            exit_code: 5,
+            condition: "values outside of the displayed range".to_string(),
+            bars: chart
+                .outliers()
+                .into_iter()
+                .map(|(i, series)| {
+                    format!("{}: {} {}", bar(i), series_name(series), value(i, series))
+                })
+                .collect(),
+        });
+    }
+    failures.retain(|f| !f.bars.is_empty());
+    failures
+}
+
+/// Display the values of each label as a box plot, in order of first appearance.
+fn print_box_plot(values: &[u32], labels: &[&str], args: &Opt, output: OutputOpt) {
+    let mut groups: Vec<&str> = vec![];
+    let mut samples: Vec<Vec<f64>> = vec![];
+    for (&label, &value) in labels.iter().zip(values) {
+        let i = match groups.iter().position(|&l| l == label) {
+            Some(i) => i,
+            None => {
+                groups.push(label);
+                samples.push(vec![]);
+                groups.len() - 1
+            }
+        };
+        samples[i].push(value as f64);
+    }
+    if groups.len() > 100 {
+        eprintln!("Too many labels for --box-plot: expected no more than 100.");
+        std::process::exit(1);
+    }
+
+    let plot = BoxPlot::new(
+        &samples,
+        BoxPlotOptions {
+            height: args.max_height.unwrap_or(16),
+            labels: Some(&groups),
+            title: args.title.as_deref(),
+            label_width: args.label_width.unwrap_or(12),
+            label_truncation: label_truncation(args),
+        },
+    );
+    match output {
+        OutputOpt::Text => print!("{plot}"),
+        OutputOpt::Png => write_png(&plot.canvas()),
+        OutputOpt::Markdown => print!("{}", plot.markdown()),
+    }
+}
+
+fn write_png(canvas: &Canvas) {
+    let stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        eprintln!("--format png writes an image: redirect it to a file, such as > chart.png");
+        std::process::exit(1);
+    }
+    if let Err(e) = canvas.write_png(stdout.lock(), &PngOptions::default()) {
+        eprintln!("Unable to write PNG: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn label_truncation(args: &Opt) -> LabelTruncation {
+    match args.label_truncation {
+        None | Some(LabelTruncationOpt::End) => LabelTruncation::End,
+        Some(LabelTruncationOpt::Middle) => LabelTruncation::Middle,
+        Some(LabelTruncationOpt::Start) => LabelTruncation::Start,
+        Some(LabelTruncationOpt::Path) => LabelTruncation::Path,
+        Some(LabelTruncationOpt::Wrap) => LabelTruncation::Wrap,
+    }
+}
+
+/// Parse `key[:asc|:desc]`. Values are sorted in descending order by default, labels ascending.
+fn parse_sort(s: &str) -> Option<Sort> {
+    let (key, order) = match s.split_once(':') {
+        Some((key, order)) => (key, Some(order)),
+        None => (s, None),
+    };
+    let key = match key {
+        "primary" => SortKey::Primary,
+        "compare" => SortKey::Comparison,
+        "difference" => SortKey::Difference,
+        "label" => SortKey::Label,
+        _ => return None,
+    };
+    let order = match (order, key) {
+        (Some("asc"), _) | (None, SortKey::Label) => SortOrder::Ascending,
+        (Some("desc"), _) | (None, _) => SortOrder::Descending,
+        _ => return None,
+    };
+    Some(Sort { key, order })
+}
+
+/// Parse a comma-separated list of statistics, or `all`.
+fn parse_summary(s: &str) -> Option<Vec<Statistic>> {
+    if s == "all" {
+        return Some(Statistic::ALL.to_vec());
+    }
+    s.split(',')
+        .map(|name| match name.trim() {
+            "count" => Some(Statistic::Count),
+            "sum" => Some(Statistic::Sum),
+            "min" => Some(Statistic::Min),
+            "max" => Some(Statistic::Max),
+            "mean" => Some(Statistic::Mean),
+            "median" => Some(Statistic::Median),
+            "stddev" => Some(Statistic::StdDev),
+            "zeros" => Some(Statistic::Zeros),
+            "outliers" => Some(Statistic::Outliers),
+            "delta" => Some(Statistic::Delta),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Read every series of an input, in the format chosen by the options.
+fn read_input(path: &str, args: &Opt, truncate: bool) -> (Vec<u32>, Vec<u32>, Vec<String>) {
+    let parse = match args.input_format {
+        None | Some(InputFormatOpt::Columns) => {
+            return InputColumns::read(path, truncate).all_series();
+        }
+        Some(InputFormatOpt::Json) => json::parse_json,
+        Some(InputFormatOpt::Ndjson) => json::parse_ndjson,
+    };
+
+    let mut text = String::new();
+    if let Err(e) = open_input(path).read_to_string(&mut text) {
+        eprintln!("Unable to read '{path}': {e}");
+        std::process::exit(1);
+    }
+    let fields = json::Fields {
+        label: args.label_field.as_deref().unwrap_or("label"),
+        value: args.value_field.as_deref().unwrap_or("value"),
+        compare: args.compare_field.as_deref().unwrap_or("compare"),
+    };
+    match parse(&text, &fields) {
+        Ok(mut series) => {
+            if truncate && series.0.len() > 100 {
+                eprintln!("Data truncated to 100 records");
+                series.0.truncate(100);
+                series.1.truncate(100);
+                series.2.truncate(100);
+            }
+            series
+        }
+        Err(e) => {
+            eprintln!("Invalid JSON in '{path}': {e}");
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Read a single series of an input, which may be labeled.
+fn read_one_series(path: &str, args: &Opt, truncate: bool) -> (Vec<u32>, Vec<String>) {
+    match args.input_format {
+        None | Some(InputFormatOpt::Columns) => InputColumns::read(path, truncate).one_series(path),
+        Some(_) => match read_input(path, args, truncate) {
+            (data, compare, labels) if compare.is_empty() => (data, labels),
+            _ => {
+                eprintln!("Invalid input '{path}': comparison values are not expected.");
+                eprintln!("For more information try --help");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+/// Read a single series from the input and compare it with the baseline of `--baseline`.
+fn read_with_baseline(
+    files: &[String],
+    args: &Opt,
+    truncate: bool,
+) -> (Vec<u32>, Vec<u32>, Vec<String>) {
+    let name = args.baseline.as_deref().unwrap();
+    let primary = match files {
+        [] => read_one_series("-", args, truncate),
+        [path] => read_one_series(path, args, truncate),
+        _ => {
+            eprintln!("Too many input files: expected no more than 1 with --baseline.");
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+    };
+    // loaded before saving, so that a baseline can be replaced by the input it's compared with
+    let compare = match baseline::load(std::path::Path::new(baseline::DIR), name) {
+        Ok(compare) => compare,
+        Err(e) => {
+            eprintln!("Unable to load baseline: {e}");
+            std::process::exit(1);
+        }
+    };
+    save_baseline(&primary.0, &primary.1, args);
+    join_series(primary, compare, &args.join, &args.fill)
+}
+
+/// Store a series as the baseline named by `--save-baseline`, if given.
+fn save_baseline(values: &[u32], labels: &[String], args: &Opt) {
+    if let Some(name) = &args.save_baseline {
+        let dir = std::path::Path::new(baseline::DIR);
+        if let Err(e) = baseline::save(dir, name, values, labels) {
+            eprintln!("Unable to save baseline: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Open a file, or the standard input stream if `path` is `-`.
+fn open_input(path: &str) -> Box<dyn BufRead> {
+    match path {
+        "-" => Box::new(std::io::stdin().lock()),
+        _ => match std::fs::File::open(path) {
+            Ok(file) => Box::new(std::io::BufReader::new(file)),
+            Err(e) => {
+                eprintln!("Unable to read '{path}': {e}");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+/// Join the values of two labeled series, returning primary, comparison and labels.
+fn join_series(
+    primary: (Vec<u32>, Vec<String>),
+    compare: (Vec<u32>, Vec<String>),
+    join: &Option<JoinOpt>,
+    fill: &Option<FillOpt>,
+) -> (Vec<u32>, Vec<u32>, Vec<String>) {
+    let labeled = !primary.1.is_empty() && !compare.1.is_empty();
+    let any_labeled = !primary.1.is_empty() || !compare.1.is_empty();
+    let by_label = match join {
+        Some(JoinOpt::Label) if !labeled => {
+            eprintln!("Invalid --join-label: both input files should contain labels.");
+            std::process::exit(1);
+        }
+        Some(JoinOpt::Label) => true,
+        Some(JoinOpt::Line) => false,
+        None => labeled,
+    };
+
+    // keys in order of appearance, primary first, with the value of each series if present
+    let mut rows: Vec<(String, Option<u32>, Option<u32>)> = if by_label {
+        fn pairs((values, labels): &(Vec<u32>, Vec<String>)) -> Vec<(&str, u32)> {
+            labels
+                .iter()
+                .map(String::as_str)
+                .zip(values.iter().copied())
+                .collect()
+        }
+        Join::Outer
+            .pair(&pairs(&primary), &pairs(&compare))
+            .into_iter()
+            .map(|(l, p, c)| (l.to_string(), p, c))
+            .collect()
+    } else {
+        (0..std::cmp::max(primary.0.len(), compare.0.len()))
+            .map(|i| {
+                let label = primary.1.get(i).or(compare.1.get(i)).cloned();
+                (
+                    label.unwrap_or_default(),
+                    primary.0.get(i).copied(),
+                    compare.0.get(i).copied(),
+                )
+            })
+            .collect()
+    };
+
+    let unmatched = rows.iter().position(|(_, p, c)| p.is_none() || c.is_none());
+    match (fill, unmatched) {
+        (Some(FillOpt::Error), Some(i)) => {
+            let key = match by_label {
+                true => format!("label '{}'", rows[i].0),
+                false => format!("line {}", i + 1),
+            };
+            eprintln!("Input files do not match: {key} is only found in one of them.");
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+        (Some(FillOpt::Drop), _) => rows.retain(|(_, p, c)| p.is_some() && c.is_some()),
+        _ => {}
+    }
+    if rows.is_empty() && matches!(fill, Some(FillOpt::Drop)) {
+        let key = if by_label { "labels" } else { "lines" };
+        eprintln!("No data: the input files have no {key} in common, with --fill-drop.");
+        std::process::exit(1);
+    }
+
+    let labels = match any_labeled {
+        true => rows.iter().map(|(l, _, _)| l.clone()).collect(),
+        false => vec![],
+    };
+    (
+        rows.iter().map(|(_, p, _)| p.unwrap_or(0)).collect(),
+        rows.iter().map(|(_, _, c)| c.unwrap_or(0)).collect(),
+        labels,
+    )
 }
 
-struct StdinColumns {
+/// Parse the options, returning the remaining arguments as input file paths.
+fn parse_args() -> (Opt, Vec<String>) {
+    let command_line = std::env::args_os()
+        .skip(1)
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    // options which aren't given on the command line may be set by the config file or environment
+    let options = Opt::options();
+    let env = std::env::vars_os()
+        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+        .collect::<Vec<_>>();
+    let path = config::path();
+    let text = match path.as_ref().map(std::fs::read_to_string) {
+        Some(Ok(text)) => Some(text),
+        Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => {
+            eprintln!("error: {}: {e}", path.unwrap().display());
+            std::process::exit(1);
+        }
+        _ => None,
+    };
+    let file = path.as_deref().zip(text.as_deref());
+    let settings = match config::resolve(&options, &command_line, &env, file) {
+        Ok((settings, warnings)) => {
+            for warning in warnings {
+                eprintln!("warning: {warning}");
+            }
+            settings
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut args = command_line
+        .iter()
+        .filter(|arg| !options.negates(arg))
+        .chain(&config::default_args(&options, &settings))
+        .map(std::ffi::OsString::from)
+        .collect::<Vec<_>>();
+    let opt = Opt::parse_internal("", &mut args);
+    let unknown = args.iter().find(|a| a.to_string_lossy().starts_with("--"));
+    let opt = match (opt, unknown) {
+        (Ok(opt), None) => opt,
+        (Err(e), _) => {
+            eprintln!("error: {e}");
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+        (_, Some(option)) => {
+            eprintln!("error: unexpected option: {}", option.to_string_lossy());
+            eprintln!("For more information try --help");
+            std::process::exit(1);
+        }
+    };
+
+    if opt.print_config {
+        match (&path, &text) {
+            (Some(path), Some(_)) => println!("# config file: {}", path.display()),
+            (Some(path), None) => println!("# config file: {} (not found)", path.display()),
+            (None, _) => println!("# config file: none, as HOME is not set"),
+        }
+        for setting in settings.iter().filter(|s| s.key != "print-config") {
+            println!("{} = {}  # {}", setting.key, setting.value, setting.source);
+        }
+        std::process::exit(0);
+    }
+
+    (
+        opt,
+        args.iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect(),
+    )
+}
+
+struct InputColumns {
     cols: [Vec<String>; 3],
 }
 
-impl StdinColumns {
+impl InputColumns {
     const DOCS: &str = r#"
-    The standard input stream should contain 1-3 columns, separated by spaces.
-    Input is truncated after 100 lines.
+    Input is read from the files given as arguments, or from the standard input stream
+    if there are none. A file named - refers to the standard input stream.
+
+    One input should contain 1-3 columns, separated by spaces.
+    Input is truncated after 100 lines, unless --top or --box-plot is used.
 
     Either:
 
@@ -104,18 +928,36 @@ impl StdinColumns {
 
     4. Labeled comparison data
     integer integer string
+
+    Two inputs should each contain 1-2 columns, either integer or integer string.
+    The first is displayed as primary data and the second as comparison data.
+
+    With --input-format-json, input should be one of:
+
+    1. An array of values
+    [3, 8, 5]
+
+    2. An object mapping labels to values
+    {"first": 3, "second": 8}
+
+    3. An array of records, with optional label and comparison fields
+    [{"label": "first", "value": 3, "compare": 4}]
+
+    With --input-format-ndjson, input should contain one record per line.
     "#;
 
-    fn new() -> Self {
+    /// Read the columns of a file, or of the standard input stream if `path` is `-`.
+    fn read(path: &str, truncate: bool) -> Self {
+        let reader = open_input(path);
         let mut col_count = 0;
         let mut col1 = vec![];
         let mut col2 = vec![];
         let mut col3 = vec![];
-        for (line_count, line) in std::io::stdin().lines().map_while(Result::ok).enumerate() {
+        for (line_count, line) in reader.lines().map_while(Result::ok).enumerate() {
             let row = line.split(' ').map(str::trim).collect::<Vec<_>>();
             if line_count == 0 {
                 col_count = row.len();
-            } else if line_count > 100 {
+            } else if truncate && line_count >= 100 {
                 eprintln!("Data truncated to 100 lines");
                 break;
             }
@@ -154,6 +996,42 @@ impl StdinColumns {
         }
     }
 
+    /// Interpret all columns: data, and optionally comparison data and labels.
+    fn all_series(&self) -> (Vec<u32>, Vec<u32>, Vec<String>) {
+        let data_primary = match self.try_integers(0) {
+            Ok(data) => data,
+            Err(_) => {
+                eprintln!("Invalid line. First column should be integers.");
+                eprintln!("For more information try --help");
+                std::process::exit(1);
+            }
+        };
+
+        match self.try_integers(1) {
+            Ok(data_compare) => (data_primary, data_compare, self.cols[2].clone()),
+            Err(_) => {
+                if !self.cols[2].is_empty() {
+                    eprintln!("Invalid line. found: integer string string");
+                    eprintln!("For more information try --help");
+                    std::process::exit(1);
+                }
+                (data_primary, vec![], self.cols[1].clone())
+            }
+        }
+    }
+
+    /// Interpret the columns as data, optionally labeled.
+    fn one_series(&self, path: &str) -> (Vec<u32>, Vec<String>) {
+        match self.try_integers(0) {
+            Ok(data) if self.cols[2].is_empty() => (data, self.cols[1].clone()),
+            _ => {
+                eprintln!("Invalid line in '{path}'. Expected: integer string");
+                eprintln!("For more information try --help");
+                std::process::exit(1);
+            }
+        }
+    }
+
     fn try_integers(&self, i: usize) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
         let mut result = vec![];
         for c in self.cols[i].iter() {
@@ -161,12 +1039,29 @@ impl StdinColumns {
         }
         Ok(result)
     }
-
-    fn strings(&self, i: usize) -> Vec<&str> {
-        self.cols[i].iter().map(|s| s.as_str()).collect()
-    }
 }
 
+const OUTPUT: &str = r#"
+    --format png writes the chart as a PNG image, drawn with a bundled bitmap font
+    in the colors of a terminal.
+
+    chart-relative --format png < values.txt > chart.png
+
+    --format markdown writes the chart as a fenced code block, followed by a table of
+    the value of each bar. In the code block, a row below the chart marks what the
+    colors of bars indicate: - improved, + regressed and * highlighted.
+"#;
+
+const EXIT_STATUS: &str = r#"
+    0   the chart was written, and passed every --fail-if check
+    1   invalid options, or unable to read input or a baseline
+    2   invalid line of input
+    3   --fail-if-above failed
+    4   --fail-if-regressed failed
+    5   --fail-if-outliers failed
+    When several checks fail, the code of the first in this list is used.
+"#;
+
 fn help_intercept() {
     let args = std::env::args().collect::<Vec<_>>();
     if args.contains(&"--help".to_string()) {
@@ -175,7 +1070,18 @@ fn help_intercept() {
             .lines()
             .filter(|l| *l != "For more information try --help")
             .collect::<Vec<_>>();
-        msg_lines.append(&mut vec!["Standard Input:", StdinColumns::DOCS]);
+        msg_lines.append(&mut vec![
+            "Input:",
+            InputColumns::DOCS,
+            "Baselines:",
+            baseline::DOCS,
+            "Config:",
+            config::DOCS,
+            "Output:",
+            OUTPUT,
+            "Exit status:",
+            EXIT_STATUS,
+        ]);
         eprintln!("{}", msg_lines.join("\n"));
         std::process::exit(1);
     }
@@ -0,0 +1,277 @@
+//! Writing charts as Markdown, for comments and wikis which don't display colors.
+
+use crate::Chart;
+use crate::canvas::{Cell, Role, Swatch};
+use crate::params::Series;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+impl Chart<'_> {
+    /// The chart as Markdown: a fenced code block holding the chart, followed by a table
+    /// of the values of each bar. Within the code block, bars keep their heights, and what
+    /// their colors indicate is marked in a row below the chart: `-` below an improved
+    /// comparison, `+` below a regressed one and `*` below a highlighted bar. The layers
+    /// of a stacked chart are filled with different patterns, such as `▓` and `▒`.
+    /// Values outside of the displayed range, which the chart indicates by `🢁` or `🢃`,
+    /// are spelled out in the table.
+    pub fn markdown(&self) -> String {
+        let canvas = self.canvas();
+        let mut lines = canvas
+            .rows()
+            .map(|row| row.iter().map(pattern).collect::<String>())
+            .collect::<Vec<_>>();
+
+        // the cues are written below the axis, which is the row below the bars
+        let cues = canvas.rows().fold(Vec::new(), |mut cues: Vec<char>, row| {
+            cues.resize(core::cmp::max(cues.len(), row.len()), ' ');
+            for (col, cell) in row.iter().enumerate() {
+                if let Some(cue) = bar_of(cell.role).and_then(|bar| self.cue(bar)) {
+                    cues[col] = cue;
+                }
+            }
+            cues
+        });
+        let last_bar_row = canvas
+            .rows()
+            .enumerate()
+            .filter(|(_, row)| row.iter().any(|c| bar_of(c.role).is_some()))
+            .map(|(n, _)| n)
+            .last();
+        if let Some(n) = last_bar_row.filter(|_| cues.iter().any(|&c| c != ' ')) {
+            let at = core::cmp::min(n + 2, lines.len());
+            lines.insert(at, cues.into_iter().collect());
+        }
+
+        let mut out = String::new();
+        write_code_block(lines.iter().map(String::as_str), &mut out);
+
+        let (data_steps, cmp_steps) = self.scale_to_steps(&self.scale());
+        let value = |v: u32, steps: i16, missing: bool| match (missing, steps) {
+            (true, _) => "missing".to_string(),
+            (_, -2) => format!("{v} (above range)"),
+            (_, -1) => format!("{v} (below range)"),
+            _ => v.to_string(),
+        };
+
+        let mut header = vec!["#"];
+        if self.labels.is_some() {
+            header.push("label");
+        }
+        header.push("primary");
+        if self.compare.is_some() {
+            header.extend(["comparison", "delta"]);
+        }
+        let rows = (0..self.data.len()).map(|i| {
+            let (primary, (pri_missing, cmp_missing)) = (self.data[i], self.missing[i]);
+            let mut row = vec![i.to_string()];
+            if let Some(labels) = &self.labels {
+                row.push(escape(labels[i]));
+            }
+            row.push(value(primary, data_steps[i], pri_missing));
+            if let (Some(compare), Some(steps)) = (&self.compare, &cmp_steps) {
+                row.push(value(compare[i], steps[i], cmp_missing));
+                row.push(match pri_missing || cmp_missing {
+                    true => String::new(),
+                    false => delta(primary, compare[i]),
+                });
+            }
+            row
+        });
+        out.push('\n');
+        write_table(&mut out, &header, rows);
+        out
+    }
+
+    /// The mark below the bar of `series` at `offset`, if its color is meaningful.
+    fn cue(&self, (offset, series): (usize, Series)) -> Option<char> {
+        let (data, compare) = self.measurements();
+        match (series, compare) {
+            (Series::Primary, _) if self.highlighted[offset] => Some('*'),
+            (Series::Comparison, Some(compare)) => match self.missing[offset] {
+                (false, false) if compare[offset] <= data[offset] => Some('-'),
+                (false, false) => Some('+'),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The difference between `compare` and `primary`, as in the summary of a chart.
+fn delta(primary: u32, compare: u32) -> String {
+    let delta = compare as i64 - primary as i64;
+    match primary {
+        0 => format!("{delta:+}"),
+        primary => format!("{delta:+} ({:+.1}%)", delta as f64 * 100.0 / primary as f64),
+    }
+}
+
+/// The offset and series of the bar which a cell is part of, or in place of.
+fn bar_of(role: Role) -> Option<(usize, Series)> {
+    match role {
+        Role::Bar { offset, series, .. }
+        | Role::ZeroMarker { offset, series }
+        | Role::OutlierMarker { offset, series }
+        | Role::MissingMarker { offset, series } => Some((offset, series)),
+        _ => None,
+    }
+}
+
+/// Patterns which fill the layers of a stacked chart, in place of their colors.
+const LAYER_PATTERNS: [char; 9] = ['█', '▓', '▒', '░', '▚', '▞', '▤', '▥', '▦'];
+
+/// The glyph of `cell`, in place of its color if the color is meaningful.
+fn pattern(cell: &Cell) -> char {
+    match (cell.role, cell.glyph) {
+        (Role::Bar { layer: Some(k), .. }, '█') | (Role::Swatch(Swatch::Layer(k)), _) => {
+            LAYER_PATTERNS[k % LAYER_PATTERNS.len()]
+        }
+        (Role::Swatch(Swatch::Improved), _) => '-',
+        (Role::Swatch(Swatch::Regressed), _) => '+',
+        (Role::Swatch(Swatch::Highlighted), _) => '*',
+        (_, glyph) => glyph,
+    }
+}
+
+/// Write `lines` as a fenced code block.
+pub(crate) fn write_code_block<'l>(lines: impl Iterator<Item = &'l str>, out: &mut String) {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    // the fence is longer than any run of backticks in the chart, such as in its title
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(core::cmp::max(3, longest_run + 1));
+    out.push_str(&format!("{fence}text\n{text}{fence}\n"));
+}
+
+/// Write a table of `rows` below `header`. The first column, and every column which
+/// holds numbers, is aligned to the right.
+pub(crate) fn write_table(
+    out: &mut String,
+    header: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) {
+    write_row(out, header.iter().copied());
+    let alignment = header.iter().map(|&name| match name {
+        "label" => "---",
+        _ => "---:",
+    });
+    write_row(out, alignment);
+    for row in rows {
+        write_row(out, row.iter().map(String::as_str));
+    }
+}
+
+fn write_row<'s>(out: &mut String, cells: impl Iterator<Item = &'s str>) {
+    for cell in cells {
+        out.push_str("| ");
+        out.push_str(cell);
+        out.push(' ');
+    }
+    out.push_str("|\n");
+}
+
+/// Escape the characters of `text` which would be read as Markdown within a table.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::params::*;
+
+    #[test]
+    fn test_markdown() {
+        let data = [10, 40, 100, 0];
+        let compare = [8, 60, 5, 7];
+        let labels = ["a|b", "c", "d", "e"];
+        let chart = Chart::new(
+            &data,
+            Some(ChartComparison { data: &compare }),
+            ChartOptions {
+                height: 4,
+                display: DisplayMode::Portrait { labels: &labels },
+                y_max: Some(60),
+                title: Some("```"),
+                legend: true,
+                ..ChartOptions::default()
+            },
+        );
+        let markdown = chart.markdown();
+        println!("\nmarkdown\n{markdown}");
+
+        // the fence is longer than the title
+        assert!(markdown.starts_with("````text\n```\n"));
+        let (block, table) = markdown.split_once("````\n\n").unwrap();
+        assert!(!block.contains('\x1b'));
+        // bars keep their heights, with a cue below the offset of each comparison
+        assert!(block.contains(" 5│▅▄ ██ 🢁▂ ⨯▃\n   0  1  2  3\n    -  +  -  +\n"));
+        assert!(block.contains("█ primary  - improved (lower)  + regressed (higher)"));
+        assert_eq!(
+            table.lines().collect::<Vec<_>>(),
+            [
+                "| # | label | primary | comparison | delta |",
+                "| ---: | --- | ---: | ---: | ---: |",
+                "| 0 | a\\|b | 10 | 8 | -2 (-20.0%) |",
+                "| 1 | c | 40 | 60 | +20 (+50.0%) |",
+                "| 2 | d | 100 (above range) | 5 | -95 (-95.0%) |",
+                "| 3 | e | 0 | 7 | +7 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_patterns() {
+        let data = [3, 9, 16, 12];
+        let chart = Chart::new(
+            &data,
+            None,
+            ChartOptions {
+                height: 2,
+                highlight: Highlight::Indices(&[1, 2]),
+                legend: true,
+                ..ChartOptions::default()
+            },
+        );
+        let markdown = chart.markdown();
+        println!("\nmarkdown highlighted\n{markdown}");
+        let block = markdown.split_once("```\n\n").unwrap().0;
+        assert!(block.contains("16│ ▁█▄\n 3│▃███\n   0123\n    **\n"));
+        assert!(block.contains("█ primary  * highlighted"));
+
+        // layers are filled with patterns in place of their colors, and keep their heights
+        let chart = Chart::stacked(
+            &[&[8][..]; 5],
+            ChartOptions {
+                height: 5,
+                legend: true,
+                ..ChartOptions::default()
+            },
+        );
+        let markdown = chart.markdown();
+        println!("\nmarkdown stacked\n{markdown}");
+        let column = markdown
+            .lines()
+            .skip(1)
+            .take(5)
+            .map(|line| line.chars().last().unwrap())
+            .collect::<String>();
+        assert_eq!(column, "▚░▒▓█");
+        assert!(markdown.contains("█ layer 0  ▓ layer 1  ▒ layer 2  ░ layer 3  ▚ layer 4"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Descriptive statistics of a series of values.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::Float;
+
+/// Statistics of a slice of `u32` values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Number of values.
+    pub count: usize,
+    /// Sum of all values.
+    pub sum: u64,
+    /// Smallest value, or 0 if there are none.
+    pub min: u32,
+    /// Largest value, or 0 if there are none.
+    pub max: u32,
+    /// Arithmetic mean.
+    pub mean: f64,
+    /// Middle value, or the mean of the two middle values.
+    pub median: f64,
+    /// Population standard deviation.
+    pub std_dev: f64,
+    /// Number of values equal to 0.
+    pub zeros: usize,
+}
+
+impl Summary {
+    /// Calculate the statistics of `values`.
+    pub fn of(values: &[u32]) -> Self {
+        let count = values.len();
+        if count == 0 {
+            return Self {
+                count,
+                sum: 0,
+                min: 0,
+                max: 0,
+                mean: 0.0,
+                median: 0.0,
+                std_dev: 0.0,
+                zeros: 0,
+            };
+        }
+
+        let sum = values.iter().map(|&v| v as u64).sum::<u64>();
+        let mean = sum as f64 / count as f64;
+
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        let median = match count % 2 {
+            0 => (sorted[count / 2 - 1] as f64 + sorted[count / 2] as f64) / 2.0,
+            _ => sorted[count / 2] as f64,
+        };
+
+        let variance = values
+            .iter()
+            .map(|&v| (v as f64 - mean) * (v as f64 - mean))
+            .sum::<f64>()
+            / count as f64;
+
+        Self {
+            count,
+            sum,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            zeros: values.iter().filter(|&&v| v == 0).count(),
+        }
+    }
+}
+
+/// The quartiles and whiskers of a box plot of `f64` samples, with Tukey's fences
+/// 1.5 interquartile ranges beyond the quartiles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxStats {
+    /// First quartile.
+    pub q1: f64,
+    /// Middle value, or the mean of the two middle values.
+    pub median: f64,
+    /// Third quartile.
+    pub q3: f64,
+    /// Smallest sample within the lower fence.
+    pub lower_whisker: f64,
+    /// Largest sample within the upper fence.
+    pub upper_whisker: f64,
+    /// Number of samples below the lower fence.
+    pub low_outliers: usize,
+    /// Number of samples above the upper fence.
+    pub high_outliers: usize,
+}
+
+impl BoxStats {
+    /// Calculate the statistics of `samples`, ignoring NaN.
+    /// Returns `None` if no samples remain.
+    pub fn of(samples: &[f64]) -> Option<Self> {
+        let mut sorted = samples
+            .iter()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .collect::<Vec<_>>();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_unstable_by(f64::total_cmp);
+
+        // interpolate between the closest ranks
+        let quantile = |p: f64| {
+            let rank = p * (sorted.len() - 1) as f64;
+            let (below, above) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+            below + (above - below) * rank.fract()
+        };
+        let (q1, median, q3) = (quantile(0.25), quantile(0.5), quantile(0.75));
+        let fence = 1.5 * (q3 - q1);
+        let (lower_fence, upper_fence) = (q1 - fence, q3 + fence);
+
+        let mut inside = sorted
+            .iter()
+            .filter(|&&v| v >= lower_fence && v <= upper_fence);
+        // the quartiles are within the fences, so some sample is too
+        let lower_whisker = *inside.next().unwrap_or(&q1);
+        let upper_whisker = *inside.next_back().unwrap_or(&lower_whisker);
+        Some(Self {
+            q1,
+            median,
+            q3,
+            lower_whisker,
+            upper_whisker,
+            low_outliers: sorted.iter().filter(|&&v| v < lower_fence).count(),
+            high_outliers: sorted.iter().filter(|&&v| v > upper_fence).count(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::of(&[2, 4, 4, 4, 5, 5, 7, 9, 0]);
+        assert_eq!(summary.count, 9);
+        assert_eq!(summary.sum, 40);
+        assert_eq!((summary.min, summary.max), (0, 9));
+        assert_eq!(summary.median, 4.0);
+        assert_eq!(summary.zeros, 1);
+        assert!((summary.mean - 4.444).abs() < 0.001);
+        assert!((summary.std_dev - 2.455).abs() < 0.001);
+
+        assert_eq!(Summary::of(&[1, 2, 3, 10]).median, 2.5);
+    }
+
+    #[test]
+    fn test_box_stats() {
+        let stats =
+            BoxStats::of(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 40.0, f64::NAN]).unwrap();
+        assert_eq!((stats.q1, stats.median, stats.q3), (3.0, 5.0, 7.0));
+        // fences at -3 and 13
+        assert_eq!((stats.lower_whisker, stats.upper_whisker), (1.0, 8.0));
+        assert_eq!((stats.low_outliers, stats.high_outliers), (0, 1));
+
+        let single = BoxStats::of(&[2.5]).unwrap();
+        assert_eq!(
+            (single.q1, single.q3, single.upper_whisker),
+            (2.5, 2.5, 2.5)
+        );
+        assert_eq!(BoxStats::of(&[f64::NAN]), None);
+    }
+}
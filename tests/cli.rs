@@ -0,0 +1,94 @@
+//! Tests of the command line interface, run as a separate process.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Run the command with `args`, writing `input` to its standard input.
+fn run(args: &[&str], input: &str) -> Output {
+    run_with_env(args, input, &[])
+}
+
+/// Run the command with `args` and the environment variables `env`.
+fn run_with_env(args: &[&str], input: &str, env: &[(&str, &str)]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chart-relative"))
+        .args(args)
+        .env_remove("CHART_RELATIVE_PROFILE")
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // the command may exit before reading its input, such as for invalid options
+    let written = child.stdin.take().unwrap().write_all(input.as_bytes());
+    if let Err(e) = written {
+        assert_eq!(e.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_empty_input() {
+    for (args, input) in [
+        (&[][..], ""),
+        (&["--input-format-json"][..], "[]"),
+        (&["--input-format-ndjson"][..], ""),
+    ] {
+        let output = run(args, input);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1), "{args:?}: {stderr}");
+        assert!(stderr.contains("no values") || stderr.contains("expected at least 1 value"));
+        assert!(!stderr.contains("panicked"), "{args:?}: {stderr}");
+        assert!(output.stdout.is_empty());
+    }
+
+    let output = run(&[], "3\n5\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_format() {
+    let output = run(&["--format", "markdown"], "3\n5\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.starts_with("```"), "{stdout}");
+
+    let output = run(&["--format", "png"], "3\n5\n");
+    assert!(output.status.success());
+    assert!(output.stdout.starts_with(b"\x89PNG"));
+
+    let output = run(&["--format", "svg"], "3\n5\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("Invalid --format 'svg'"), "{stderr}");
+}
+
+#[test]
+fn test_config_env() {
+    let env = [
+        ("CHART_RELATIVE_LEGEND", "true"),
+        ("CHART_RELATIVE_COLOUR", "1"),
+    ];
+    let output = run_with_env(&[], "3\n5\n", &env);
+    let (stdout, stderr) = (
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    assert!(output.status.success());
+    assert!(stdout.contains("primary"), "{stdout}");
+    assert!(stderr.contains("CHART_RELATIVE_COLOUR: unknown option 'colour'"));
+
+    let output = run_with_env(&["--no-legend"], "3\n5\n", &env);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("primary"));
+}
+
+#[test]
+fn test_highlight_out_of_range() {
+    let output = run(&["--highlight", "1,4"], "3\n5\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("Invalid --highlight 4"), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+}
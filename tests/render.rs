@@ -0,0 +1,162 @@
+//! Rendering tests, with color disabled.
+//!
+//! Expected output is kept in `tests/snapshots`. After an intended change to the output,
+//! run `UPDATE_SNAPSHOTS=1 cargo test --test render` and review the difference.
+
+use chart_relative::{Chart, params::*};
+use proptest::prelude::*;
+use std::path::Path;
+
+fn render(chart: &Chart) -> String {
+    colored::control::set_override(false);
+    chart.to_string()
+}
+
+/// Compare `output` with the content of `tests/snapshots/{name}.txt`.
+fn assert_snapshot(name: &str, output: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, output).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {e}. Run with UPDATE_SNAPSHOTS=1 to create it.",
+            path.display()
+        )
+    });
+    assert_eq!(output, expected, "output differs from snapshot '{name}'");
+}
+
+// both small values and outliers, so that each view hides some of them
+const MIXED: [u32; 8] = [0, 3, 14, 9, 520, 1, 730, 22];
+const LABELS: [&str; 8] = [
+    "zero",
+    "three",
+    "fourteen",
+    "nine",
+    "five hundred and twenty",
+    "one",
+    "seven hundred and thirty",
+    "twenty two",
+];
+
+#[test]
+fn test_views_and_display_modes() {
+    for view_name in ["bottom", "top"] {
+        for (display_name, display) in [
+            ("compact", DisplayMode::Compact),
+            ("portrait", DisplayMode::Portrait { labels: &LABELS }),
+        ] {
+            let chart = Chart::new(
+                &MIXED,
+                None,
+                ChartOptions {
+                    height: 4,
+                    view: match view_name {
+                        "top" => ViewPreference::Top,
+                        _ => ViewPreference::Bottom,
+                    },
+                    display,
+                    ..ChartOptions::default()
+                },
+            );
+            assert_snapshot(&format!("{view_name}_{display_name}"), &render(&chart));
+        }
+    }
+}
+
+#[test]
+fn test_comparison() {
+    let compare = [2, 3, 11, 12, 600, 0, 410, 22];
+    for (name, display) in [
+        ("comparison_compact", DisplayMode::Compact),
+        (
+            "comparison_portrait",
+            DisplayMode::Portrait { labels: &LABELS },
+        ),
+    ] {
+        let chart = Chart::new(
+            &MIXED,
+            Some(ChartComparison { data: &compare }),
+            ChartOptions {
+                height: 4,
+                view: ViewPreference::Bottom,
+                display,
+                ..ChartOptions::default()
+            },
+        );
+        assert_snapshot(name, &render(&chart));
+    }
+}
+
+#[test]
+fn test_two_digit_width() {
+    let data = [23, 32, 44, 0, 2, 44, 5, 23, 42, 29, 16];
+    for len in [10, 11] {
+        let chart = Chart::new(&data[..len], None, ChartOptions::default());
+        let output = render(&chart);
+        let bars = output.lines().next().unwrap().split('│').nth(1).unwrap();
+        assert_eq!(bars.chars().count(), if len <= 10 { len } else { len * 2 });
+        assert_snapshot(&format!("width_{len}_bars"), &output);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_layout(
+        data in prop::collection::vec(0u32..5000, 1..=100),
+        compare in prop::bool::ANY,
+        portrait in prop::bool::ANY,
+        top in prop::bool::ANY,
+        height in 1u16..=16,
+    ) {
+        let compare_data = data.iter().rev().copied().collect::<Vec<_>>();
+        let labels = (0..data.len()).map(|i| format!("label {i}")).collect::<Vec<_>>();
+        let labels = labels.iter().map(|l| l.as_str()).collect::<Vec<_>>();
+        let chart = Chart::new(
+            &data,
+            compare.then_some(ChartComparison { data: &compare_data }),
+            ChartOptions {
+                height,
+                view: if top { ViewPreference::Top } else { ViewPreference::Bottom },
+                display: match portrait {
+                    true => DisplayMode::Portrait { labels: &labels },
+                    false => DisplayMode::Compact,
+                },
+                ..ChartOptions::default()
+            },
+        );
+        let output = render(&chart);
+        let lines = output.lines().collect::<Vec<_>>();
+
+        // a line for each layer, then offsets, each as wide as the bars next to the tick labels
+        let slot_chars = match (compare, data.len()) {
+            (true, _) => 3,
+            (false, len) if len <= 10 => 1,
+            (false, _) => 2,
+        };
+        let sidebar_chars = lines[0].chars().position(|c| c == '│').unwrap() + 1;
+        let chart_chars = sidebar_chars + data.len() * slot_chars;
+        for line in &lines[..height as usize] {
+            prop_assert_eq!(line.chars().position(|c| c == '│'), Some(sidebar_chars - 1));
+            prop_assert_eq!(line.chars().count(), chart_chars);
+        }
+        prop_assert_eq!(lines[height as usize].chars().count(), chart_chars);
+
+        // then a table of labels, in columns 17 characters wide
+        let table = &lines[height as usize + 1..];
+        match portrait {
+            true => {
+                let col_count = std::cmp::max((chart_chars - 1) / 17, 1);
+                prop_assert_eq!(table.len(), data.len().div_ceil(col_count));
+                for line in table {
+                    prop_assert!(line.chars().count() <= col_count * 17);
+                }
+            }
+            false => prop_assert!(table.is_empty()),
+        }
+    }
+}